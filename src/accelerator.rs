@@ -0,0 +1,143 @@
+//! Accelerator table resources.
+//!
+//! Accelerator tables are stored in `RT_ACCELERATOR` resources as a list of 8-byte `ACCELTABLEENTRY` records.
+//! The last record in the table is marked with the `0x80` flag.
+//! See <https://learn.microsoft.com/en-us/windows/win32/menurc/acceltableentry> for more information.
+
+use alloc::{format, vec::Vec};
+use core::{borrow::Borrow, mem::size_of};
+
+use zerocopy::IntoBytes;
+
+use crate::{constants::*, errors::*, resource::*, types::*, util::*};
+
+/// Keyboard accelerator.
+/// This is an entry in an accelerator table.
+///
+/// The `flags` contain the `FVIRTKEY`, `FNOINVERT`, `FSHIFT`, `FCONTROL` and `FALT` flags.
+/// The last-entry flag is derived from the position in the table when building.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, Default)]
+pub struct Accelerator {
+    pub flags: u16,
+    pub key:   u16,
+    pub id:    u16,
+}
+
+/// Accelerator table resource.
+/// This is the content of an `RT_ACCELERATOR` resource.
+#[derive(Debug, Clone, Eq, PartialEq, Default)]
+pub struct AcceleratorTable {
+    pub accelerators: Vec<Accelerator>,
+}
+impl AcceleratorTable {
+    /// Parse an accelerator table from a byte slice.
+    /// Parsing stops at the entry marked as the last entry.
+    ///
+    /// # Returns
+    /// Returns an error if the table is truncated before the last entry.
+    pub fn parse(data: &[u8]) -> Result<Self, ReadError> {
+        let mut accelerators = Vec::new();
+        let mut offset = 0;
+        loop {
+            let entry = read::<AcceleratorTableEntry>(data.get(offset..).unwrap_or_default())
+                .map_err(|_| {
+                    ReadError(format!(
+                        "accelerator table is truncated at entry {}",
+                        accelerators.len()
+                    ))
+                })?;
+            accelerators.push(Accelerator {
+                flags: entry.flags & !ACCELERATOR_LAST_ENTRY,
+                key:   entry.key,
+                id:    entry.id,
+            });
+            offset += size_of::<AcceleratorTableEntry>();
+            if entry.flags & ACCELERATOR_LAST_ENTRY != 0 {
+                break;
+            }
+        }
+        Ok(Self { accelerators })
+    }
+
+    /// Build the accelerator table into raw bytes to be included in a resource table.
+    pub fn build(&self) -> Vec<u8> {
+        let mut data =
+            Vec::with_capacity(self.accelerators.len() * size_of::<AcceleratorTableEntry>());
+        for (i, accelerator) in self.accelerators.iter().enumerate() {
+            let mut flags = accelerator.flags & !ACCELERATOR_LAST_ENTRY;
+            if i == self.accelerators.len() - 1 {
+                flags |= ACCELERATOR_LAST_ENTRY;
+            }
+            data.extend(
+                AcceleratorTableEntry {
+                    flags,
+                    key: accelerator.key,
+                    id: accelerator.id,
+                    padding: 0,
+                }
+                .as_bytes(),
+            );
+        }
+        data
+    }
+}
+
+impl ResourceDirectory {
+    /// Get an accelerator table.
+    ///
+    /// # Returns
+    /// Returns `None` if the accelerator table does not exist.
+    /// Returns an error if the accelerator resource directory or the accelerator table is invalid.
+    pub fn get_accelerator_table<N: Borrow<ResourceEntryName>>(
+        &self, name: N, language: LANGID,
+    ) -> Result<Option<AcceleratorTable>, ResourceError> {
+        let data = self.data_entry(
+            &ResourceEntryName::ID(RT_ACCELERATOR as u32),
+            name.borrow(),
+            language,
+            "accelerator",
+        )?;
+        match data {
+            Some(data) => Ok(Some(AcceleratorTable::parse(data.data())?)),
+            None => Ok(None),
+        }
+    }
+
+    /// Set an accelerator table.
+    ///
+    /// This will overwrite the existing accelerator table with the given name and language.
+    /// Setting an empty accelerator table removes the table.
+    ///
+    /// # Returns
+    /// Returns an error if the resource table structure is not well-formed.
+    pub fn set_accelerator_table<N: Borrow<ResourceEntryName>>(
+        &mut self, name: N, language: LANGID, table: &AcceleratorTable,
+    ) -> Result<(), ResourceError> {
+        if table.accelerators.is_empty() {
+            return self.remove_accelerator_table(name, language);
+        }
+        self.set_data_entry(
+            &ResourceEntryName::ID(RT_ACCELERATOR as u32),
+            name.borrow(),
+            language,
+            table.build(),
+            "accelerator",
+        )
+    }
+
+    /// Remove an accelerator table.
+    ///
+    /// # Returns
+    /// Returns an error if the resource table structure is not well-formed.
+    pub fn remove_accelerator_table<N: Borrow<ResourceEntryName>>(
+        &mut self, name: N, language: LANGID,
+    ) -> Result<(), ResourceError> {
+        self.remove_data_entry(
+            &ResourceEntryName::ID(RT_ACCELERATOR as u32),
+            name.borrow(),
+            language,
+            "accelerator",
+        )?;
+        Ok(())
+    }
+}
@@ -0,0 +1,382 @@
+//! Animated cursor and icon resources.
+//!
+//! Animated cursors and icons are stored in `RT_ANICURSOR` and `RT_ANIICON` resources as RIFF `ACON` containers.
+//! The container holds an `anih` header, optional `rate` and `seq ` chunks, and a `fram` list with one icon or cursor file per frame.
+//! See <https://learn.microsoft.com/en-us/windows/win32/menurc/using-cursors> for more information.
+
+use alloc::{format, string::String, vec::Vec};
+use core::{borrow::Borrow, iter, mem::size_of};
+
+use zerocopy::IntoBytes;
+
+use crate::{
+    constants::*,
+    cursor::{CursorFile, image_dimensions},
+    errors::*,
+    resource::*,
+    types::*,
+    util::*,
+};
+
+/// Animated cursor or icon.
+/// This is the content of an `.ani` file and of `RT_ANICURSOR` and `RT_ANIICON` resources.
+///
+/// Each frame is the content of an `.ico` or `.cur` file.
+/// Display rates are given in jiffies (1/60 of a second).
+/// The `rates` and `sequence` contain one entry per animation step if present.
+#[derive(Debug, Clone, Eq, PartialEq, Default)]
+pub struct AnimatedCursor {
+    pub display_rate: u32,
+    pub frames:       Vec<Vec<u8>>,
+    pub rates:        Option<Vec<u32>>,
+    pub sequence:     Option<Vec<u32>>,
+    pub title:        Option<String>,
+    pub artist:       Option<String>,
+}
+impl AnimatedCursor {
+    /// Create an animated icon from frames in any format that can be converted to icon data.
+    /// The frames are stored as `.ico` files, use [`from_cursors`](Self::from_cursors) for animated cursors.
+    ///
+    /// # Returns
+    /// Returns an error if a frame is not a valid icon.
+    pub fn from_icons<T: ToIcon>(frames: &[T], display_rate: u32) -> Result<Self, ResourceError> {
+        Ok(Self {
+            display_rate,
+            frames: frames
+                .iter()
+                .map(|frame| Ok(build_icon_file(&frame.icons()?)))
+                .collect::<Result<_, ResourceError>>()?,
+            ..Default::default()
+        })
+    }
+
+    /// Create an animated cursor from cursor frames.
+    /// The frames are stored as `.cur` files with their hotspots.
+    ///
+    /// # Returns
+    /// Returns an error if an image of a frame is neither a DIB nor a PNG image.
+    pub fn from_cursors(frames: &[CursorFile], display_rate: u32) -> Result<Self, ResourceError> {
+        Ok(Self {
+            display_rate,
+            frames: frames.iter().map(CursorFile::build).collect::<Result<_, ReadError>>()?,
+            ..Default::default()
+        })
+    }
+
+    #[cfg(feature = "images")]
+    /// Create an animated cursor from a sequence of images.
+    /// Each image is resized to the given cursor size and stored as a `.cur` file with the given hotspot.
+    /// Cursors with a size below 256 pixels are stored as bitmaps, which all Windows versions support.
+    ///
+    /// # Returns
+    /// Returns an error if the size is not supported, the hotspot is outside of the cursor or an image could not be converted.
+    pub fn from_images(
+        frames: &[DynamicImage], size: u32, hotspot: (u16, u16), display_rate: u32,
+    ) -> Result<Self, ResourceError> {
+        if hotspot.0 as u32 >= size || hotspot.1 as u32 >= size {
+            return Err(ResourceError::InvalidBytes(
+                format!("cursor hotspot {:?} is outside of the cursor size {}", hotspot, size)
+                    .into(),
+            ));
+        }
+        let options = crate::IconOptions::new().sizes(&[size]).png_from(256);
+        let frames = frames
+            .iter()
+            .map(|frame| {
+                let images = options
+                    .icons(frame)?
+                    .into_iter()
+                    .map(|icon| crate::CursorImage {
+                        hotspot_x: hotspot.0,
+                        hotspot_y: hotspot.1,
+                        data:      icon[14..].to_vec(),
+                    })
+                    .collect();
+                Ok(CursorFile { images })
+            })
+            .collect::<Result<Vec<_>, ResourceError>>()?;
+        Self::from_cursors(&frames, display_rate)
+    }
+
+    /// Returns the frame at the given index in the icon data representation of [`ToIcon::icons`].
+    /// Cursor frames are converted to icon frames, discarding the hotspot.
+    ///
+    /// # Returns
+    /// Returns `None` if the frame does not exist.
+    /// Returns an error if the frame is not a valid icon or cursor.
+    pub fn frame_icons(&self, index: usize) -> Result<Option<Vec<Vec<u8>>>, ResourceError> {
+        let frame = match self.frames.get(index) {
+            Some(frame) => frame,
+            None => return Ok(None),
+        };
+        let directory = read::<IconDirectory>(frame)?;
+        if directory.type_ != 2 {
+            return Ok(Some(frame.icons()?));
+        }
+        let mut frame = frame.clone();
+        frame[2] = 1;
+        for i in 0..directory.count as usize {
+            let entry = 6 + i * 16;
+            let size = read::<u32>(frame.get(entry + 8..).unwrap_or_default())? as usize;
+            let offset = read::<u32>(frame.get(entry + 12..).unwrap_or_default())? as usize;
+            let (_, _, planes, bit_count) =
+                image_dimensions(frame.get(offset..offset + size).unwrap_or_default())?;
+            frame[entry + 4..entry + 6].copy_from_slice(&planes.to_le_bytes());
+            frame[entry + 6..entry + 8].copy_from_slice(&bit_count.to_le_bytes());
+        }
+        Ok(Some(frame.icons()?))
+    }
+
+    /// Parse an animated cursor from a byte slice.
+    ///
+    /// # Returns
+    /// Returns an error if the data is not a well-formed RIFF `ACON` container.
+    pub fn parse(data: &[u8]) -> Result<Self, ReadError> {
+        if data.len() < 12 || &data[0..4] != b"RIFF" || &data[8..12] != b"ACON" {
+            return Err("animated cursor data is not a RIFF ACON container".into());
+        }
+        let end = (8 + read::<u32>(&data[4..])? as usize).min(data.len());
+
+        let mut animated = Self::default();
+        let mut header = None;
+        for (id, chunk) in riff_chunks(&data[12..end])? {
+            match id {
+                b"anih" => header = Some(read::<AnimatedCursorHeader>(chunk)?),
+                b"rate" => animated.rates = Some(read_u32_list(chunk)?),
+                b"seq " => animated.sequence = Some(read_u32_list(chunk)?),
+                b"LIST" if chunk.starts_with(b"fram") => {
+                    for (id, chunk) in riff_chunks(&chunk[4..])? {
+                        if id == b"icon" {
+                            animated.frames.push(chunk.to_vec());
+                        }
+                    }
+                }
+                b"LIST" if chunk.starts_with(b"INFO") => {
+                    for (id, chunk) in riff_chunks(&chunk[4..])? {
+                        let string = chunk
+                            .iter()
+                            .take_while(|&&c| c != 0)
+                            .map(|&c| c as char)
+                            .collect::<String>();
+                        match id {
+                            b"INAM" => animated.title = Some(string),
+                            b"IART" => animated.artist = Some(string),
+                            _ => {}
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        let header = header.ok_or("animated cursor header is missing")?;
+        if header.flags & AF_ICON == 0 {
+            return Err("animated cursor frames are raw bitmaps".into());
+        }
+        if header.frames as usize != animated.frames.len() {
+            return Err(ReadError(format!(
+                "animated cursor frame count {} does not match header frame count {}",
+                animated.frames.len(),
+                { header.frames }
+            )));
+        }
+        animated.display_rate = header.display_rate;
+        Ok(animated)
+    }
+
+    /// Build the animated cursor into raw bytes to be included in a resource table or written as an `.ani` file.
+    pub fn build(&self) -> Vec<u8> {
+        let mut chunks = Vec::new();
+        chunks.extend(b"ACON");
+
+        if self.title.is_some() || self.artist.is_some() {
+            let mut info = Vec::new();
+            info.extend(b"INFO");
+            for (id, string) in [(b"INAM", &self.title), (b"IART", &self.artist)] {
+                if let Some(string) = string {
+                    let mut data = string
+                        .chars()
+                        .map(|c| if (c as u32) < 0x100 { c as u8 } else { b'?' })
+                        .collect::<Vec<_>>();
+                    data.push(0);
+                    push_riff_chunk(&mut info, id, &data);
+                }
+            }
+            push_riff_chunk(&mut chunks, b"LIST", &info);
+        }
+
+        let steps = self
+            .sequence
+            .as_ref()
+            .or(self.rates.as_ref())
+            .map(|steps| steps.len())
+            .unwrap_or(self.frames.len());
+        let mut flags = AF_ICON;
+        if self.sequence.is_some() {
+            flags |= AF_SEQUENCE;
+        }
+        push_riff_chunk(
+            &mut chunks,
+            b"anih",
+            AnimatedCursorHeader {
+                size: size_of::<AnimatedCursorHeader>() as u32,
+                frames: self.frames.len() as u32,
+                steps: steps as u32,
+                display_rate: self.display_rate,
+                flags,
+                ..Default::default()
+            }
+            .as_bytes(),
+        );
+        if let Some(rates) = &self.rates {
+            push_riff_chunk(&mut chunks, b"rate", rates.as_bytes());
+        }
+        if let Some(sequence) = &self.sequence {
+            push_riff_chunk(&mut chunks, b"seq ", sequence.as_bytes());
+        }
+
+        let mut frames = Vec::new();
+        frames.extend(b"fram");
+        for frame in &self.frames {
+            push_riff_chunk(&mut frames, b"icon", frame);
+        }
+        push_riff_chunk(&mut chunks, b"LIST", &frames);
+
+        let mut data = Vec::new();
+        push_riff_chunk(&mut data, b"RIFF", &chunks);
+        data
+    }
+}
+
+/// RIFF chunk ID and data.
+type RiffChunk<'a> = (&'a [u8; 4], &'a [u8]);
+
+/// Split RIFF chunk data into its chunks.
+fn riff_chunks(mut data: &[u8]) -> Result<Vec<RiffChunk<'_>>, ReadError> {
+    let mut chunks = Vec::new();
+    while data.len() >= 8 {
+        let id = <&[u8; 4]>::try_from(&data[0..4]).unwrap();
+        let size = read::<u32>(&data[4..])? as usize;
+        if 8 + size > data.len() {
+            return Err(ReadError(format!(
+                "riff chunk {:?} is truncated",
+                String::from_utf8_lossy(id)
+            )));
+        }
+        chunks.push((id, &data[8..8 + size]));
+        data = &data[(8 + aligned_to(size, 2)).min(data.len())..];
+    }
+    Ok(chunks)
+}
+
+fn push_riff_chunk(data: &mut Vec<u8>, id: &[u8; 4], chunk: &[u8]) {
+    data.extend(id);
+    data.extend((chunk.len() as u32).to_le_bytes());
+    data.extend(chunk);
+    data.extend(iter::repeat_n(0, aligned_to(chunk.len(), 2) - chunk.len()));
+}
+
+fn read_u32_list(data: &[u8]) -> Result<Vec<u32>, ReadError> {
+    data.chunks_exact(4).map(read::<u32>).collect()
+}
+
+impl ResourceDirectory {
+    /// Get an animated cursor.
+    ///
+    /// # Returns
+    /// Returns `None` if the animated cursor does not exist.
+    /// Returns an error if the animated cursor resource directory or the animated cursor is invalid.
+    pub fn get_animated_cursor<N: Borrow<ResourceEntryName>>(
+        &self, name: N, language: LANGID,
+    ) -> Result<Option<AnimatedCursor>, ResourceError> {
+        self.get_animated(RT_ANICURSOR, name.borrow(), language, "animated cursor")
+    }
+
+    /// Set an animated cursor.
+    ///
+    /// This will overwrite the existing animated cursor with the given name and language.
+    ///
+    /// # Returns
+    /// Returns an error if the resource table structure is not well-formed.
+    pub fn set_animated_cursor<N: Borrow<ResourceEntryName>>(
+        &mut self, name: N, language: LANGID, cursor: &AnimatedCursor,
+    ) -> Result<(), ResourceError> {
+        self.set_data_entry(
+            &ResourceEntryName::ID(RT_ANICURSOR as u32),
+            name.borrow(),
+            language,
+            cursor.build(),
+            "animated cursor",
+        )
+    }
+
+    /// Remove an animated cursor.
+    ///
+    /// # Returns
+    /// Returns an error if the resource table structure is not well-formed.
+    pub fn remove_animated_cursor<N: Borrow<ResourceEntryName>>(
+        &mut self, name: N, language: LANGID,
+    ) -> Result<(), ResourceError> {
+        self.remove_data_entry(
+            &ResourceEntryName::ID(RT_ANICURSOR as u32),
+            name.borrow(),
+            language,
+            "animated cursor",
+        )?;
+        Ok(())
+    }
+
+    /// Get an animated icon.
+    ///
+    /// # Returns
+    /// Returns `None` if the animated icon does not exist.
+    /// Returns an error if the animated icon resource directory or the animated icon is invalid.
+    pub fn get_animated_icon<N: Borrow<ResourceEntryName>>(
+        &self, name: N, language: LANGID,
+    ) -> Result<Option<AnimatedCursor>, ResourceError> {
+        self.get_animated(RT_ANIICON, name.borrow(), language, "animated icon")
+    }
+
+    /// Set an animated icon.
+    ///
+    /// This will overwrite the existing animated icon with the given name and language.
+    ///
+    /// # Returns
+    /// Returns an error if the resource table structure is not well-formed.
+    pub fn set_animated_icon<N: Borrow<ResourceEntryName>>(
+        &mut self, name: N, language: LANGID, icon: &AnimatedCursor,
+    ) -> Result<(), ResourceError> {
+        self.set_data_entry(
+            &ResourceEntryName::ID(RT_ANIICON as u32),
+            name.borrow(),
+            language,
+            icon.build(),
+            "animated icon",
+        )
+    }
+
+    /// Remove an animated icon.
+    ///
+    /// # Returns
+    /// Returns an error if the resource table structure is not well-formed.
+    pub fn remove_animated_icon<N: Borrow<ResourceEntryName>>(
+        &mut self, name: N, language: LANGID,
+    ) -> Result<(), ResourceError> {
+        self.remove_data_entry(
+            &ResourceEntryName::ID(RT_ANIICON as u32),
+            name.borrow(),
+            language,
+            "animated icon",
+        )?;
+        Ok(())
+    }
+
+    fn get_animated(
+        &self, type_: WORD, name: &ResourceEntryName, language: LANGID, kind: &str,
+    ) -> Result<Option<AnimatedCursor>, ResourceError> {
+        match self.data_entry(&ResourceEntryName::ID(type_ as u32), name, language, kind)? {
+            Some(data) => Ok(Some(AnimatedCursor::parse(data.data())?)),
+            None => Ok(None),
+        }
+    }
+}
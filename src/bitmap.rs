@@ -0,0 +1,182 @@
+//! Bitmap resources.
+//!
+//! Bitmaps are stored in `RT_BITMAP` resources as a device-independent bitmap (DIB) without the `BITMAPFILEHEADER` of a `.bmp` file.
+//! See <https://learn.microsoft.com/en-us/windows/win32/gdi/bitmap-storage> for more information.
+
+use alloc::{format, vec::Vec};
+use core::{borrow::Borrow, mem::size_of};
+
+use zerocopy::IntoBytes;
+
+#[cfg(feature = "images")]
+use crate::DynamicImage;
+use crate::{constants::*, errors::*, resource::*, types::*, util::*};
+
+/// Trait for data types that can be converted to bitmap resource data.
+///
+/// This trait is implemented for the content of `.bmp` files as `&[u8]` and `Vec<u8>`, and for `DynamicImage` when the `images` feature is enabled.
+pub trait ToBitmap {
+    fn bitmap(&self) -> Result<Vec<u8>, ResourceError>;
+}
+impl ToBitmap for &[u8] {
+    fn bitmap(&self) -> Result<Vec<u8>, ResourceError> {
+        let header = read::<BitmapFileHeader>(self)?;
+        if header.type_ != u16::from_le_bytes(*b"BM") {
+            return Err(ResourceError::InvalidBytes("bitmap data is not a bitmap".into()));
+        }
+        let dib = &self[size_of::<BitmapFileHeader>()..];
+        let pixel_offset = bitmap_pixel_offset(dib)?;
+        let pixels = self.get(header.off_bits as usize..).ok_or_else(|| {
+            ResourceError::InvalidBytes(
+                format!("bitmap pixel data offset {:#x?} is out of bounds", { header.off_bits })
+                    .into(),
+            )
+        })?;
+        let mut data = Vec::with_capacity(pixel_offset + pixels.len());
+        data.extend(&dib[..pixel_offset]);
+        data.extend(pixels);
+        Ok(data)
+    }
+}
+impl ToBitmap for Vec<u8> {
+    fn bitmap(&self) -> Result<Vec<u8>, ResourceError> { self.as_slice().bitmap() }
+}
+#[cfg(feature = "images")]
+impl ToBitmap for &DynamicImage {
+    fn bitmap(&self) -> Result<Vec<u8>, ResourceError> {
+        use image::ImageFormat;
+        use std::io::Cursor;
+        // images with transparency are encoded as 32-bit bitmaps with an alpha mask, all others as 24-bit bitmaps
+        let mut data = Vec::new();
+        if self.color().has_alpha() {
+            self.to_rgba8().write_to(&mut Cursor::new(&mut data), ImageFormat::Bmp)?;
+        } else {
+            self.to_rgb8().write_to(&mut Cursor::new(&mut data), ImageFormat::Bmp)?;
+        }
+        data.bitmap()
+    }
+}
+#[cfg(feature = "images")]
+impl ToBitmap for DynamicImage {
+    fn bitmap(&self) -> Result<Vec<u8>, ResourceError> { (&self).bitmap() }
+}
+
+/// Returns the offset of the pixel data in a DIB, which follows the header, the color masks and the color table.
+pub(crate) fn bitmap_pixel_offset(dib: &[u8]) -> Result<usize, ReadError> {
+    let size = read::<u32>(dib)? as usize;
+    let offset = if size == 12 {
+        // BITMAPCOREHEADER with 3-byte color table entries
+        let bit_count = read::<u16>(dib.get(10..).unwrap_or_default())?;
+        let colors = if bit_count <= 8 { 1 << bit_count } else { 0 };
+        size + colors * 3
+    } else {
+        if size < size_of::<BitmapInfoHeader>() {
+            return Err(ReadError(format!("invalid bitmap header size: {}", size)));
+        }
+        let header = read::<BitmapInfoHeader>(dib)?;
+        let masks = match (size, header.compression) {
+            (40, BI_BITFIELDS) => 12,
+            (40, BI_ALPHABITFIELDS) => 16,
+            _ => 0,
+        };
+        let colors = match header.clr_used {
+            0 if header.bit_count <= 8 => 1 << header.bit_count,
+            0 => 0,
+            colors => colors as usize,
+        };
+        size + masks + colors * 4
+    };
+    if offset > dib.len() {
+        return Err("bitmap data is truncated".into());
+    }
+    Ok(offset)
+}
+
+impl ResourceDirectory {
+    /// Get a bitmap as the content of a `.bmp` file.
+    ///
+    /// # Returns
+    /// Returns `None` if the bitmap does not exist.
+    /// Returns an error if the bitmap resource directory or the bitmap is invalid.
+    pub fn get_bitmap<N: Borrow<ResourceEntryName>>(
+        &self, name: N, language: LANGID,
+    ) -> Result<Option<Vec<u8>>, ResourceError> {
+        let dib = self.data_entry(
+            &ResourceEntryName::ID(RT_BITMAP as u32),
+            name.borrow(),
+            language,
+            "bitmap",
+        )?;
+        let dib = match dib {
+            Some(dib) => dib.data(),
+            None => return Ok(None),
+        };
+        let header_size = size_of::<BitmapFileHeader>();
+        let mut data = Vec::with_capacity(header_size + dib.len());
+        data.extend(
+            BitmapFileHeader {
+                type_:     u16::from_le_bytes(*b"BM"),
+                size:      (header_size + dib.len()) as u32,
+                reserved1: 0,
+                reserved2: 0,
+                off_bits:  (header_size + bitmap_pixel_offset(dib)?) as u32,
+            }
+            .as_bytes(),
+        );
+        data.extend(dib);
+        Ok(Some(data))
+    }
+
+    #[cfg(feature = "images")]
+    /// Get a bitmap decoded as an image.
+    ///
+    /// # Returns
+    /// Returns `None` if the bitmap does not exist.
+    /// Returns an error if the bitmap resource directory is invalid or the bitmap could not be decoded.
+    pub fn get_bitmap_image<N: Borrow<ResourceEntryName>>(
+        &self, name: N, language: LANGID,
+    ) -> Result<Option<DynamicImage>, ResourceError> {
+        match self.get_bitmap(name, language)? {
+            Some(data) => {
+                Ok(Some(image::load_from_memory_with_format(&data, image::ImageFormat::Bmp)?))
+            }
+            None => Ok(None),
+        }
+    }
+
+    /// Set a bitmap.
+    /// The bitmap must be the byte slice of a `.bmp` file, or a [`image::DynamicImage`] when the `images` feature is enabled.
+    ///
+    /// This will overwrite the existing bitmap with the given name and language.
+    ///
+    /// # Returns
+    /// Returns an error if the bitmap is invalid or the resource table structure is not well-formed.
+    pub fn set_bitmap<N: Borrow<ResourceEntryName>, T: ToBitmap>(
+        &mut self, name: N, language: LANGID, bitmap: T,
+    ) -> Result<(), ResourceError> {
+        let data = bitmap.bitmap()?;
+        self.set_data_entry(
+            &ResourceEntryName::ID(RT_BITMAP as u32),
+            name.borrow(),
+            language,
+            data,
+            "bitmap",
+        )
+    }
+
+    /// Remove a bitmap.
+    ///
+    /// # Returns
+    /// Returns an error if the resource table structure is not well-formed.
+    pub fn remove_bitmap<N: Borrow<ResourceEntryName>>(
+        &mut self, name: N, language: LANGID,
+    ) -> Result<(), ResourceError> {
+        self.remove_data_entry(
+            &ResourceEntryName::ID(RT_BITMAP as u32),
+            name.borrow(),
+            language,
+            "bitmap",
+        )?;
+        Ok(())
+    }
+}
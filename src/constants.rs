@@ -24,6 +24,8 @@ pub const VS_SPECIAL_BUILD: &str = "SpecialBuild";
 
 // https://docs.microsoft.com/en-us/openspecs/windows_protocols/ms-lcid/a9eac961-e77d-41a6-90a5-ce1a8b0cdb9c
 pub const LANGUAGE_ID_EN_US: LANGID = 1033; // 0x0409, en-US
+pub const LANG_NEUTRAL: LANGID = 0x00;
+pub const SUBLANG_NEUTRAL: LANGID = 0x00;
 // https://docs.microsoft.com/en-us/openspecs/windows_protocols/ms-ucoderef/28fefe92-d66c-4b03-90a9-97b473223d43
 pub const CODE_PAGE_ID_EN_US: LANGID = 1200; // 0x04B0, UTF-16LE
 
@@ -39,6 +41,7 @@ pub const VOS_OS216: DWORD = 0x00020000;
 pub const VOS_OS232: DWORD = 0x00030000;
 pub const VOS__PM16: DWORD = 0x00000002;
 pub const VOS__PM32: DWORD = 0x00000003;
+pub const VOS_NT_WINDOWS32: DWORD = VOS_NT | VOS__WINDOWS32;
 
 pub const VFT_UNKNOWN: DWORD = 0x00000000;
 pub const VFT_APP: DWORD = 0x00000001;
@@ -64,6 +67,14 @@ pub const VFT2_FONT_RASTER: DWORD = 0x00000001;
 pub const VFT2_FONT_TRUETYPE: DWORD = 0x00000003;
 pub const VFT2_FONT_VECTOR: DWORD = 0x00000002;
 
+pub const VS_FF_DEBUG: DWORD = 0x00000001;
+pub const VS_FF_PRERELEASE: DWORD = 0x00000002;
+pub const VS_FF_PATCHED: DWORD = 0x00000004;
+pub const VS_FF_PRIVATEBUILD: DWORD = 0x00000008;
+pub const VS_FF_INFOINFERRED: DWORD = 0x00000010;
+pub const VS_FF_SPECIALBUILD: DWORD = 0x00000020;
+pub const VS_FFI_FILEFLAGSMASK: DWORD = 0x0000003F;
+
 pub const VS_FIXEDFILEINFO_SIGNATURE: DWORD = 0xFEEF04BD;
 pub const VS_FIXEDFILEINFO_VERSION: DWORD = 0x00010000;
 
@@ -102,6 +113,110 @@ pub const RT_HTML: WORD = 0x17;
 pub const RT_MANIFEST: WORD = 0x18;
 
 
+// https://learn.microsoft.com/en-us/windows/win32/dlgbox/dialog-box-styles
+
+pub const DS_ABSALIGN: DWORD = 0x00000001;
+pub const DS_SYSMODAL: DWORD = 0x00000002;
+pub const DS_LOCALEDIT: DWORD = 0x00000020;
+pub const DS_SETFONT: DWORD = 0x00000040;
+pub const DS_MODALFRAME: DWORD = 0x00000080;
+pub const DS_NOIDLEMSG: DWORD = 0x00000100;
+pub const DS_SETFOREGROUND: DWORD = 0x00000200;
+pub const DS_3DLOOK: DWORD = 0x00000004;
+pub const DS_FIXEDSYS: DWORD = 0x00000008;
+pub const DS_NOFAILCREATE: DWORD = 0x00000010;
+pub const DS_CONTROL: DWORD = 0x00000400;
+pub const DS_CENTER: DWORD = 0x00000800;
+pub const DS_CENTERMOUSE: DWORD = 0x00001000;
+pub const DS_CONTEXTHELP: DWORD = 0x00002000;
+pub const DS_SHELLFONT: DWORD = DS_SETFONT | DS_FIXEDSYS;
+
+// https://learn.microsoft.com/en-us/windows/win32/api/winuser/ns-winuser-dlgitemtemplate
+
+pub const DIALOG_CLASS_BUTTON: WORD = 0x0080;
+pub const DIALOG_CLASS_EDIT: WORD = 0x0081;
+pub const DIALOG_CLASS_STATIC: WORD = 0x0082;
+pub const DIALOG_CLASS_LISTBOX: WORD = 0x0083;
+pub const DIALOG_CLASS_SCROLLBAR: WORD = 0x0084;
+pub const DIALOG_CLASS_COMBOBOX: WORD = 0x0085;
+
+// https://learn.microsoft.com/en-us/windows/win32/menurc/normalmenuitem
+
+pub const MF_GRAYED: WORD = 0x0001;
+pub const MF_DISABLED: WORD = 0x0002;
+pub const MF_BITMAP: WORD = 0x0004;
+pub const MF_CHECKED: WORD = 0x0008;
+pub const MF_POPUP: WORD = 0x0010;
+pub const MF_MENUBARBREAK: WORD = 0x0020;
+pub const MF_MENUBREAK: WORD = 0x0040;
+pub const MF_END: WORD = 0x0080;
+pub const MF_OWNERDRAW: WORD = 0x0100;
+pub const MF_SEPARATOR: WORD = 0x0800;
+pub const MF_HELP: WORD = 0x4000;
+
+// https://learn.microsoft.com/en-us/windows/win32/menurc/menuex-template-item
+
+pub const MFT_STRING: DWORD = 0x00000000;
+pub const MFT_BITMAP: DWORD = 0x00000004;
+pub const MFT_MENUBARBREAK: DWORD = 0x00000020;
+pub const MFT_MENUBREAK: DWORD = 0x00000040;
+pub const MFT_OWNERDRAW: DWORD = 0x00000100;
+pub const MFT_RADIOCHECK: DWORD = 0x00000200;
+pub const MFT_SEPARATOR: DWORD = 0x00000800;
+pub const MFT_RIGHTORDER: DWORD = 0x00002000;
+pub const MFT_RIGHTJUSTIFY: DWORD = 0x00004000;
+
+pub const MFS_ENABLED: DWORD = 0x00000000;
+pub const MFS_UNCHECKED: DWORD = 0x00000000;
+pub const MFS_UNHILITE: DWORD = 0x00000000;
+pub const MFS_GRAYED: DWORD = 0x00000003;
+pub const MFS_DISABLED: DWORD = 0x00000003;
+pub const MFS_CHECKED: DWORD = 0x00000008;
+pub const MFS_HILITE: DWORD = 0x00000080;
+pub const MFS_DEFAULT: DWORD = 0x00001000;
+
+// https://learn.microsoft.com/en-us/windows/win32/menurc/acceltableentry
+
+pub const FVIRTKEY: WORD = 0x0001;
+pub const FNOINVERT: WORD = 0x0002;
+pub const FSHIFT: WORD = 0x0004;
+pub const FCONTROL: WORD = 0x0008;
+pub const FALT: WORD = 0x0010;
+pub const ACCELERATOR_LAST_ENTRY: WORD = 0x0080;
+
+// https://learn.microsoft.com/en-us/windows/win32/menurc/using-cursors
+
+pub const AF_ICON: DWORD = 0x00000001;
+pub const AF_SEQUENCE: DWORD = 0x00000002;
+
+// https://learn.microsoft.com/en-us/windows/win32/api/wingdi/ns-wingdi-bitmapinfoheader
+
+pub const BI_RGB: DWORD = 0;
+pub const BI_RLE8: DWORD = 1;
+pub const BI_RLE4: DWORD = 2;
+pub const BI_BITFIELDS: DWORD = 3;
+pub const BI_JPEG: DWORD = 4;
+pub const BI_PNG: DWORD = 5;
+pub const BI_ALPHABITFIELDS: DWORD = 6;
+
+// https://learn.microsoft.com/en-us/windows/win32/sbscs/using-side-by-side-assemblies-as-a-resource
+
+pub const CREATEPROCESS_MANIFEST_RESOURCE_ID: WORD = 1;
+pub const ISOLATIONAWARE_MANIFEST_RESOURCE_ID: WORD = 2;
+pub const ISOLATIONAWARE_NOSTATICIMPORT_MANIFEST_RESOURCE_ID: WORD = 3;
+
+// https://learn.microsoft.com/en-us/windows/win32/sbscs/application-manifests#supportedos
+
+pub const SUPPORTED_OS_WINDOWS_VISTA: &str = "{e2011457-1546-43c5-a5fe-008deee3d3f0}";
+pub const SUPPORTED_OS_WINDOWS_7: &str = "{35138b9a-5d96-4fbd-8e2d-a2440225f93a}";
+pub const SUPPORTED_OS_WINDOWS_8: &str = "{4a2f28e3-53b9-4441-ba9c-d69d4a4a6e38}";
+pub const SUPPORTED_OS_WINDOWS_8_1: &str = "{1f676c76-80e1-4239-95bb-83d0f6d0da78}";
+pub const SUPPORTED_OS_WINDOWS_10: &str = "{8e0f7a12-bfb3-4fe8-b9a5-48fd50a15a9a}";
+
+// https://learn.microsoft.com/en-us/windows/win32/sbscs/application-manifests#activecodepage
+
+pub const MANIFEST_CODE_PAGE_UTF8: &str = "UTF-8";
+
 // https://docs.microsoft.com/en-us/windows/win32/debug/pe-format#section-flags
 
 pub const IMAGE_SCN_TYPE_NO_PAD: DWORD = 0x00000008;
@@ -0,0 +1,295 @@
+//! Cursor resources.
+//!
+//! Cursor images are stored in `RT_CURSOR` resources with a 4-byte hotspot prefix,
+//! and are grouped by `RT_GROUP_CURSOR` resources containing a cursor directory.
+//! See <https://learn.microsoft.com/en-us/windows/win32/menurc/resource-file-formats> for more information.
+
+use alloc::vec::Vec;
+use core::{borrow::Borrow, mem::size_of};
+
+use zerocopy::IntoBytes;
+
+use crate::{constants::*, errors::*, resource::*, types::*, util::*};
+
+const PNG_SIGNATURE: &[u8] = b"\x89PNG\r\n\x1a\n";
+
+/// Returns the width, height, planes and bit count of a DIB or PNG image.
+/// The height of a DIB is halved to exclude the AND mask.
+pub(crate) fn image_dimensions(data: &[u8]) -> Result<(u32, u32, u16, u16), ReadError> {
+    if data.starts_with(PNG_SIGNATURE) {
+        let width = u32::from_be_bytes(read::<[u8; 4]>(data.get(16..).unwrap_or_default())?);
+        let height = u32::from_be_bytes(read::<[u8; 4]>(data.get(20..).unwrap_or_default())?);
+        let bit_depth = read::<u8>(data.get(24..).unwrap_or_default())? as u16;
+        let channels = match read::<u8>(data.get(25..).unwrap_or_default())? {
+            0 | 3 => 1,
+            4 => 2,
+            2 => 3,
+            _ => 4,
+        };
+        return Ok((width, height, 1, bit_depth * channels));
+    }
+    let header = read::<BitmapInfoHeader>(data)?;
+    Ok((
+        header.width.unsigned_abs(),
+        header.height.unsigned_abs() / 2,
+        header.planes,
+        header.bit_count,
+    ))
+}
+
+/// Cursor image.
+/// This is a single image in a cursor file or cursor group, consisting of the hotspot and the DIB or PNG image data.
+#[derive(Debug, Clone, Eq, PartialEq, Default)]
+pub struct CursorImage {
+    pub hotspot_x: u16,
+    pub hotspot_y: u16,
+    pub data:      Vec<u8>,
+}
+impl CursorImage {
+    /// Parse the content of an `RT_CURSOR` resource.
+    ///
+    /// # Returns
+    /// Returns an error if the data is too small to contain the hotspot.
+    pub fn parse(data: &[u8]) -> Result<Self, ReadError> {
+        if data.len() < 4 {
+            return Err("cursor data is too small".into());
+        }
+        Ok(Self {
+            hotspot_x: read::<u16>(data)?,
+            hotspot_y: read::<u16>(&data[2..])?,
+            data:      data[4..].to_vec(),
+        })
+    }
+
+    /// Build the cursor image into the content of an `RT_CURSOR` resource.
+    pub fn build(&self) -> Vec<u8> {
+        let mut data = Vec::with_capacity(4 + self.data.len());
+        data.extend(self.hotspot_x.to_le_bytes());
+        data.extend(self.hotspot_y.to_le_bytes());
+        data.extend(&self.data);
+        data
+    }
+
+    /// Returns the width, height, planes and bit count of the image.
+    ///
+    /// # Returns
+    /// Returns an error if the image data is neither a DIB nor a PNG image.
+    pub fn dimensions(&self) -> Result<(u32, u32, u16, u16), ReadError> {
+        image_dimensions(&self.data)
+    }
+}
+
+/// Cursor file.
+/// This is the content of a `.cur` file, which can be stored as a cursor group in the resource directory.
+#[derive(Debug, Clone, Eq, PartialEq, Default)]
+pub struct CursorFile {
+    pub images: Vec<CursorImage>,
+}
+impl CursorFile {
+    /// Parse a cursor from the content of a `.cur` file.
+    ///
+    /// # Returns
+    /// Returns an error if the data is not a valid cursor file.
+    pub fn parse(data: &[u8]) -> Result<Self, ReadError> {
+        let directory = read::<IconDirectory>(data)?;
+        if directory.type_ != 2 {
+            return Err("cursor data is not a cursor".into());
+        }
+        let mut images = Vec::with_capacity(directory.count as usize);
+        for i in 0..directory.count as usize {
+            let entry = data.get(6 + i * 16..6 + i * 16 + 16).ok_or("cursor data is too small")?;
+            let size = read::<u32>(&entry[8..])? as usize;
+            let offset = read::<u32>(&entry[12..])? as usize;
+            if offset + size > data.len() {
+                return Err("cursor data is truncated".into());
+            }
+            images.push(CursorImage {
+                hotspot_x: read::<u16>(&entry[4..])?,
+                hotspot_y: read::<u16>(&entry[6..])?,
+                data:      data[offset..offset + size].to_vec(),
+            });
+        }
+        Ok(Self { images })
+    }
+
+    /// Build the cursor into the content of a `.cur` file.
+    ///
+    /// # Returns
+    /// Returns an error if an image is neither a DIB nor a PNG image.
+    pub fn build(&self) -> Result<Vec<u8>, ReadError> {
+        let mut data = Vec::new();
+        data.extend(
+            IconDirectory {
+                reserved: 0,
+                type_:    2,
+                count:    self.images.len() as u16,
+            }
+            .as_bytes(),
+        );
+        let mut offset = 6 + self.images.len() * 16;
+        for image in &self.images {
+            let (width, height, ..) = image.dimensions()?;
+            data.extend([width as u8, height as u8, 0, 0]);
+            data.extend(image.hotspot_x.to_le_bytes());
+            data.extend(image.hotspot_y.to_le_bytes());
+            data.extend((image.data.len() as u32).to_le_bytes());
+            data.extend((offset as u32).to_le_bytes());
+            offset += image.data.len();
+        }
+        for image in &self.images {
+            data.extend(&image.data);
+        }
+        Ok(data)
+    }
+}
+
+impl ResourceDirectory {
+    /// Get a cursor group.
+    ///
+    /// # Returns
+    /// Returns `None` if the cursor group does not exist.
+    /// Returns an error if the cursor resource directories are invalid.
+    pub fn get_cursor<N: Borrow<ResourceEntryName>>(
+        &self, name: N, language: LANGID,
+    ) -> Result<Option<CursorFile>, ResourceError> {
+        let group = self.data_entry(
+            &ResourceEntryName::ID(RT_GROUP_CURSOR as u32),
+            name.borrow(),
+            language,
+            "group cursor",
+        )?;
+        let group = match group {
+            Some(group) => group,
+            None => return Ok(None),
+        };
+        let mut images = Vec::new();
+        for entry in cursor_directory_entries(group.data())? {
+            let data = self.group_member(RT_CURSOR, entry.id, language, "cursor")?;
+            images.push(CursorImage::parse(data)?);
+        }
+        Ok(Some(CursorFile { images }))
+    }
+
+    /// Set a cursor group.
+    /// The cursor images are added with new IDs following the highest existing cursor ID.
+    ///
+    /// This will overwrite the cursor group with the given name and language in place and keep all other cursor groups intact.
+    /// Cursor images referenced only by the replaced cursor group are removed.
+    ///
+    /// # Returns
+    /// Returns an error if a cursor image is not a valid image, is higher than 32767 pixels
+    /// or the resource table structure is not well-formed.
+    pub fn set_cursor<N: Borrow<ResourceEntryName>>(
+        &mut self, name: N, language: LANGID, cursor: &CursorFile,
+    ) -> Result<(), ResourceError> {
+        let name = name.borrow();
+        let dimensions = cursor
+            .images
+            .iter()
+            .map(|image| image.dimensions())
+            .collect::<Result<Vec<_>, _>>()?;
+        // the group entry stores the width and the height including the AND mask as 16-bit values
+        if dimensions
+            .iter()
+            .any(|(width, height, ..)| *width > u16::MAX as u32 || *height > (u16::MAX / 2) as u32)
+        {
+            return Err(ResourceError::InvalidBytes("cursor image is too large".into()));
+        }
+        let replaced = self
+            .data_entry(
+                &ResourceEntryName::ID(RT_GROUP_CURSOR as u32),
+                name,
+                language,
+                "group cursor",
+            )?
+            .map(|group| cursor_directory_entries(group.data()))
+            .transpose()?;
+
+        let cursor_table = self
+            .root
+            .table_or_insert(ResourceEntryName::ID(RT_CURSOR as u32), "cursor table")?;
+        let first_free_cursor_id =
+            cursor_table.next_free_ids(cursor.images.len(), "cursor table")?;
+
+        let mut group = Vec::new();
+        group.extend(
+            IconDirectory {
+                reserved: 0,
+                type_:    2,
+                count:    cursor.images.len() as u16,
+            }
+            .as_bytes(),
+        );
+        for (i, (image, (width, height, planes, bit_count))) in
+            cursor.images.iter().zip(dimensions).enumerate()
+        {
+            let id = first_free_cursor_id + i as u16;
+            let data = image.build();
+            group.extend(
+                CursorDirectoryEntry {
+                    width: width as u16,
+                    // the height includes the AND mask as in the bitmap header
+                    height: height as u16 * 2,
+                    planes,
+                    bit_count,
+                    bytes: data.len() as u32,
+                    id,
+                }
+                .as_bytes(),
+            );
+            let mut inner_table = ResourceTable::default();
+            inner_table.insert(
+                ResourceEntryName::ID(language as u32),
+                ResourceEntry::Data(ResourceData {
+                    data:     data.into(),
+                    codepage: CODE_PAGE_ID_EN_US as u32,
+                    reserved: 0,
+                }),
+            );
+            cursor_table
+                .insert(ResourceEntryName::ID(id as u32), ResourceEntry::Table(inner_table));
+        }
+
+        // replace the group in place and remove the replaced images afterwards
+        self.set_data_entry(
+            &ResourceEntryName::ID(RT_GROUP_CURSOR as u32),
+            name,
+            language,
+            group,
+            "group cursor",
+        )?;
+        if let Some(replaced) = replaced {
+            self.remove_group_members(
+                RT_GROUP_CURSOR,
+                RT_CURSOR,
+                replaced.iter().map(|entry| entry.id),
+                "cursor",
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Remove a cursor group and all cursor images uniquely referenced by it.
+    ///
+    /// # Returns
+    /// Returns an error if the cursor resource directories are invalid.
+    pub fn remove_cursor<N: Borrow<ResourceEntryName>>(
+        &mut self, name: N, language: LANGID,
+    ) -> Result<(), ResourceError> {
+        self.remove_group(RT_GROUP_CURSOR, RT_CURSOR, name.borrow(), language, "cursor")
+    }
+}
+
+/// Read the entries of a cursor group directory.
+pub(crate) fn cursor_directory_entries(
+    data: &[u8],
+) -> Result<Vec<CursorDirectoryEntry>, ReadError> {
+    let directory = read::<IconDirectory>(data)?;
+    (0..directory.count as usize)
+        .map(|i| {
+            read::<CursorDirectoryEntry>(
+                data.get(6 + i * size_of::<CursorDirectoryEntry>()..).unwrap_or_default(),
+            )
+        })
+        .collect()
+}
@@ -0,0 +1,365 @@
+//! Dialog template resources.
+//!
+//! Dialogs are stored in `RT_DIALOG` resources either as a classic `DLGTEMPLATE` or as an extended `DLGTEMPLATEEX`.
+//! See <https://learn.microsoft.com/en-us/windows/win32/dlgbox/about-dialog-boxes#templates-in-memory> for more information.
+
+use alloc::{format, string::String, vec::Vec};
+use core::{borrow::Borrow, iter, mem::size_of};
+
+use zerocopy::IntoBytes;
+
+use crate::{constants::*, errors::*, resource::*, types::*, util::*};
+
+/// Name or ordinal value in a dialog template.
+///
+/// Menus, window classes and control titles can be referenced either by a 16-bit ordinal or by name.
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub enum NameOrOrdinal {
+    Ordinal(u16),
+    Name(String),
+}
+impl NameOrOrdinal {
+    /// Parse an optional name or ordinal, returning the value and the number of bytes read.
+    fn parse(data: &[u8]) -> Result<(Option<Self>, usize), ReadError> {
+        match read::<u16>(data)? {
+            0x0000 => Ok((None, 2)),
+            0xffff => Ok((Some(Self::Ordinal(read::<u16>(&data[2..])?)), 4)),
+            _ => {
+                let (name, length) = read_u16_string_terminated(data)?;
+                Ok((Some(Self::Name(name)), length))
+            }
+        }
+    }
+
+    fn build(value: &Option<Self>, data: &mut Vec<u8>) {
+        match value {
+            None => data.extend([0, 0]),
+            Some(Self::Ordinal(ordinal)) => {
+                data.extend(0xffffu16.to_le_bytes());
+                data.extend(ordinal.to_le_bytes());
+            }
+            Some(Self::Name(name)) => data.extend(string_to_u16(name)),
+        }
+    }
+}
+impl From<u16> for NameOrOrdinal {
+    fn from(ordinal: u16) -> Self { Self::Ordinal(ordinal) }
+}
+impl From<&str> for NameOrOrdinal {
+    fn from(name: &str) -> Self { Self::Name(name.into()) }
+}
+
+/// Dialog font.
+/// This is only present in a dialog template if the `DS_SETFONT` style is set.
+///
+/// The `weight`, `italic` and `charset` fields are only stored in extended dialog templates.
+#[derive(Debug, Clone, Eq, PartialEq, Default)]
+pub struct DialogFont {
+    pub point_size: u16,
+    pub weight:     u16,
+    pub italic:     u8,
+    pub charset:    u8,
+    pub typeface:   String,
+}
+
+/// Dialog control.
+/// This is an item in a dialog template.
+///
+/// The `help_id` field is only stored in extended dialog templates, and the `id` is truncated to 16 bits in classic dialog templates.
+#[derive(Debug, Clone, Eq, PartialEq, Default)]
+pub struct DialogControl {
+    pub help_id:       u32,
+    pub ex_style:      u32,
+    pub style:         u32,
+    pub x:             i16,
+    pub y:             i16,
+    pub cx:            i16,
+    pub cy:            i16,
+    pub id:            u32,
+    pub class:         Option<NameOrOrdinal>,
+    pub title:         Option<NameOrOrdinal>,
+    pub creation_data: Vec<u8>,
+}
+
+/// Dialog template resource.
+/// This is the content of an `RT_DIALOG` resource in either the classic or the extended format.
+///
+/// The font is written when it is set, and the `DS_SETFONT` style is updated accordingly when building.
+#[derive(Debug, Clone, Eq, PartialEq, Default)]
+pub struct DialogTemplate {
+    pub extended: bool,
+    pub help_id:  u32,
+    pub ex_style: u32,
+    pub style:    u32,
+    pub x:        i16,
+    pub y:        i16,
+    pub cx:       i16,
+    pub cy:       i16,
+    pub menu:     Option<NameOrOrdinal>,
+    pub class:    Option<NameOrOrdinal>,
+    pub title:    String,
+    pub font:     Option<DialogFont>,
+    pub controls: Vec<DialogControl>,
+}
+impl DialogTemplate {
+    /// Parse a dialog template from a byte slice.
+    ///
+    /// # Returns
+    /// Returns an error if the dialog template is not well-formed.
+    pub fn parse(data: &[u8]) -> Result<Self, ReadError> {
+        let extended =
+            data.len() >= 4 && read::<u16>(data)? == 1 && read::<u16>(&data[2..])? == 0xffff;
+
+        let mut dialog = Self {
+            extended,
+            ..Default::default()
+        };
+        let count;
+        let mut offset;
+        if extended {
+            let header = read::<DialogTemplateExHeader>(data)?;
+            dialog.help_id = header.help_id;
+            dialog.ex_style = header.ex_style;
+            dialog.style = header.style;
+            (dialog.x, dialog.y, dialog.cx, dialog.cy) = (header.x, header.y, header.cx, header.cy);
+            count = header.count;
+            offset = size_of::<DialogTemplateExHeader>();
+        } else {
+            let header = read::<DialogTemplateHeader>(data)?;
+            dialog.ex_style = header.ex_style;
+            dialog.style = header.style;
+            (dialog.x, dialog.y, dialog.cx, dialog.cy) = (header.x, header.y, header.cx, header.cy);
+            count = header.count;
+            offset = size_of::<DialogTemplateHeader>();
+        }
+
+        let (menu, length) = NameOrOrdinal::parse(data.get(offset..).unwrap_or_default())?;
+        dialog.menu = menu;
+        offset += length;
+        let (class, length) = NameOrOrdinal::parse(data.get(offset..).unwrap_or_default())?;
+        dialog.class = class;
+        offset += length;
+        let (title, length) = read_u16_string_terminated(data.get(offset..).unwrap_or_default())?;
+        dialog.title = title;
+        offset += length;
+
+        if dialog.style & DS_SETFONT != 0 {
+            let mut font = DialogFont {
+                point_size: read::<u16>(data.get(offset..).unwrap_or_default())?,
+                ..Default::default()
+            };
+            offset += 2;
+            if extended {
+                font.weight = read::<u16>(data.get(offset..).unwrap_or_default())?;
+                font.italic = read::<u8>(data.get(offset + 2..).unwrap_or_default())?;
+                font.charset = read::<u8>(data.get(offset + 3..).unwrap_or_default())?;
+                offset += 4;
+            }
+            let (typeface, length) =
+                read_u16_string_terminated(data.get(offset..).unwrap_or_default())?;
+            font.typeface = typeface;
+            offset += length;
+            dialog.font = Some(font);
+        }
+
+        for i in 0..count {
+            offset = aligned_to(offset, 4);
+            let mut control = DialogControl::default();
+            if extended {
+                let header =
+                    read::<DialogItemTemplateExHeader>(data.get(offset..).unwrap_or_default())
+                        .map_err(|_| ReadError(format!("dialog control {} is truncated", i)))?;
+                control.help_id = header.help_id;
+                control.ex_style = header.ex_style;
+                control.style = header.style;
+                (control.x, control.y, control.cx, control.cy) =
+                    (header.x, header.y, header.cx, header.cy);
+                control.id = header.id;
+                offset += size_of::<DialogItemTemplateExHeader>();
+            } else {
+                let header =
+                    read::<DialogItemTemplateHeader>(data.get(offset..).unwrap_or_default())
+                        .map_err(|_| ReadError(format!("dialog control {} is truncated", i)))?;
+                control.ex_style = header.ex_style;
+                control.style = header.style;
+                (control.x, control.y, control.cx, control.cy) =
+                    (header.x, header.y, header.cx, header.cy);
+                control.id = header.id as u32;
+                offset += size_of::<DialogItemTemplateHeader>();
+            }
+            let (class, length) = NameOrOrdinal::parse(data.get(offset..).unwrap_or_default())?;
+            control.class = class;
+            offset += length;
+            let (title, length) = NameOrOrdinal::parse(data.get(offset..).unwrap_or_default())?;
+            control.title = title;
+            offset += length;
+            let creation_data_length =
+                read::<u16>(data.get(offset..).unwrap_or_default())? as usize;
+            offset += 2;
+            if offset + creation_data_length > data.len() {
+                return Err(ReadError(format!("dialog control {} creation data is truncated", i)));
+            }
+            control.creation_data = data[offset..offset + creation_data_length].to_vec();
+            offset += creation_data_length;
+            dialog.controls.push(control);
+        }
+
+        Ok(dialog)
+    }
+
+    /// Build the dialog template into raw bytes to be included in a resource table.
+    pub fn build(&self) -> Vec<u8> {
+        let mut data = Vec::new();
+
+        let style = match self.font {
+            Some(_) => self.style | DS_SETFONT,
+            None => self.style & !DS_SETFONT,
+        };
+        if self.extended {
+            data.extend(
+                DialogTemplateExHeader {
+                    version: 1,
+                    signature: 0xffff,
+                    help_id: self.help_id,
+                    ex_style: self.ex_style,
+                    style,
+                    count: self.controls.len() as u16,
+                    x: self.x,
+                    y: self.y,
+                    cx: self.cx,
+                    cy: self.cy,
+                }
+                .as_bytes(),
+            );
+        } else {
+            data.extend(
+                DialogTemplateHeader {
+                    style,
+                    ex_style: self.ex_style,
+                    count: self.controls.len() as u16,
+                    x: self.x,
+                    y: self.y,
+                    cx: self.cx,
+                    cy: self.cy,
+                }
+                .as_bytes(),
+            );
+        }
+        NameOrOrdinal::build(&self.menu, &mut data);
+        NameOrOrdinal::build(&self.class, &mut data);
+        data.extend(string_to_u16(&self.title));
+
+        if let Some(font) = &self.font {
+            data.extend(font.point_size.to_le_bytes());
+            if self.extended {
+                data.extend(font.weight.to_le_bytes());
+                data.extend([font.italic, font.charset]);
+            }
+            data.extend(string_to_u16(&font.typeface));
+        }
+
+        for control in &self.controls {
+            data.extend(iter::repeat_n(0, aligned_to(data.len(), 4) - data.len()));
+            if self.extended {
+                data.extend(
+                    DialogItemTemplateExHeader {
+                        help_id:  control.help_id,
+                        ex_style: control.ex_style,
+                        style:    control.style,
+                        x:        control.x,
+                        y:        control.y,
+                        cx:       control.cx,
+                        cy:       control.cy,
+                        id:       control.id,
+                    }
+                    .as_bytes(),
+                );
+            } else {
+                data.extend(
+                    DialogItemTemplateHeader {
+                        style:    control.style,
+                        ex_style: control.ex_style,
+                        x:        control.x,
+                        y:        control.y,
+                        cx:       control.cx,
+                        cy:       control.cy,
+                        id:       control.id as u16,
+                    }
+                    .as_bytes(),
+                );
+            }
+            NameOrOrdinal::build(&control.class, &mut data);
+            NameOrOrdinal::build(&control.title, &mut data);
+            data.extend((control.creation_data.len() as u16).to_le_bytes());
+            data.extend(&control.creation_data);
+        }
+
+        data
+    }
+
+    /// Returns the control with the given ID.
+    pub fn control(&self, id: u32) -> Option<&DialogControl> {
+        self.controls.iter().find(|control| control.id == id)
+    }
+
+    /// Returns the mutable control with the given ID.
+    pub fn control_mut(&mut self, id: u32) -> Option<&mut DialogControl> {
+        self.controls.iter_mut().find(|control| control.id == id)
+    }
+}
+
+impl ResourceDirectory {
+    /// Get a dialog template.
+    ///
+    /// # Returns
+    /// Returns `None` if the dialog does not exist.
+    /// Returns an error if the dialog resource directory or the dialog template is invalid.
+    pub fn get_dialog<N: Borrow<ResourceEntryName>>(
+        &self, name: N, language: LANGID,
+    ) -> Result<Option<DialogTemplate>, ResourceError> {
+        let data = self.data_entry(
+            &ResourceEntryName::ID(RT_DIALOG as u32),
+            name.borrow(),
+            language,
+            "dialog",
+        )?;
+        match data {
+            Some(data) => Ok(Some(DialogTemplate::parse(data.data())?)),
+            None => Ok(None),
+        }
+    }
+
+    /// Set a dialog template.
+    ///
+    /// This will overwrite the existing dialog with the given name and language.
+    ///
+    /// # Returns
+    /// Returns an error if the resource table structure is not well-formed.
+    pub fn set_dialog<N: Borrow<ResourceEntryName>>(
+        &mut self, name: N, language: LANGID, dialog: &DialogTemplate,
+    ) -> Result<(), ResourceError> {
+        self.set_data_entry(
+            &ResourceEntryName::ID(RT_DIALOG as u32),
+            name.borrow(),
+            language,
+            dialog.build(),
+            "dialog",
+        )
+    }
+
+    /// Remove a dialog template.
+    ///
+    /// # Returns
+    /// Returns an error if the resource table structure is not well-formed.
+    pub fn remove_dialog<N: Borrow<ResourceEntryName>>(
+        &mut self, name: N, language: LANGID,
+    ) -> Result<(), ResourceError> {
+        self.remove_data_entry(
+            &ResourceEntryName::ID(RT_DIALOG as u32),
+            name.borrow(),
+            language,
+            "dialog",
+        )?;
+        Ok(())
+    }
+}
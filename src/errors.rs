@@ -74,6 +74,8 @@ pub enum ResourceError {
     InvalidTable(String),
     #[cfg_attr(feature = "std", error("invalid bytes: {0}"))]
     InvalidBytes(ReadError),
+    #[cfg_attr(feature = "std", error("language conflict: {0}"))]
+    LanguageConflict(String),
     #[cfg(feature = "images")]
     #[error("invalid icon: {0}")]
     InvalidIconResource(ImageError),
@@ -0,0 +1,730 @@
+//! Icon group resources.
+//!
+//! Icon images are stored in `RT_ICON` resources and are grouped by `RT_GROUP_ICON` resources containing an icon directory.
+//! The icon directory of a group references the icon images by resource ID instead of the file offsets of an `.ico` file.
+//! See <https://learn.microsoft.com/en-us/windows/win32/menurc/resource-file-formats> for more information.
+
+use alloc::{format, vec::Vec};
+use core::{borrow::Borrow, mem::size_of};
+
+use foldhash::fast::RandomState;
+use indexmap::{IndexMap, IndexSet};
+use zerocopy::IntoBytes;
+
+#[cfg(feature = "images")]
+use crate::{DynamicImage, FilterType};
+use crate::{constants::*, errors::*, resource::*, types::*, util::*};
+
+/// Icon group.
+/// This is an entry in the list returned by [`ResourceDirectory::icon_groups`].
+///
+/// The `entries` contain the metadata of the icon images in the group, where a width or height of `0` means 256 pixels.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct IconGroup {
+    pub name:     ResourceEntryName,
+    pub language: LANGID,
+    pub entries:  Vec<IconDirectoryEntry>,
+}
+
+/// Icon and cursor images removed by [`ResourceDirectory::remove_unreferenced_images`].
+#[derive(Debug, Clone, Eq, PartialEq, Default)]
+pub struct RemovedImages {
+    pub icons:   Vec<ResourceEntryName>,
+    pub cursors: Vec<ResourceEntryName>,
+}
+impl RemovedImages {
+    /// Returns if no images were removed.
+    pub fn is_empty(&self) -> bool { self.icons.is_empty() && self.cursors.is_empty() }
+}
+
+impl ResourceDirectory {
+    /// Returns all icon groups in all languages.
+    ///
+    /// # Returns
+    /// Returns an error if the group icon resource directory is invalid.
+    pub fn icon_groups(&self) -> Result<Vec<IconGroup>, ResourceError> {
+        let group_table = match self
+            .root
+            .table(ResourceEntryName::ID(RT_GROUP_ICON as u32), "group icon table")?
+        {
+            Some(table) => table,
+            None => return Ok(Vec::new()),
+        };
+        let mut groups = Vec::new();
+        for (name, inner_table) in group_table.entries.iter() {
+            let inner_table = inner_table.as_table().ok_or_else(|| {
+                ResourceError::InvalidTable("inner group icon table is not a table".into())
+            })?;
+            for (language, entry) in inner_table.entries.iter() {
+                let language = match language {
+                    ResourceEntryName::ID(language) => *language as LANGID,
+                    ResourceEntryName::Name(_) => continue,
+                };
+                let data = entry.as_data().ok_or_else(|| {
+                    ResourceError::InvalidTable("group icon table entry is not data".into())
+                })?;
+                groups.push(IconGroup {
+                    name: name.clone(),
+                    language,
+                    entries: icon_directory_entries(data.data())?,
+                });
+            }
+        }
+        Ok(groups)
+    }
+
+    /// Set an icon group.
+    /// The icon must be the byte slice of a valid icon, or a [`image::DynamicImage`] when the `images` feature is enabled.
+    /// The icon images are added with new IDs following the highest existing icon ID.
+    ///
+    /// When `icon` is a [`image::DynamicImage`], the image is resized to the different icon resolutions.
+    ///
+    /// This will overwrite the icon group with the given name and language in place and keep all other icon groups intact.
+    /// Icon images referenced only by the replaced icon group are removed.
+    ///
+    /// # Returns
+    /// Returns an error if the icon is not a valid image or the resource table structure is not well-formed.
+    pub fn set_icon<N: Borrow<ResourceEntryName>, T: ToIcon>(
+        &mut self, name: N, language: LANGID, icon: T,
+    ) -> Result<(), ResourceError> {
+        let name = name.borrow();
+        let icons = icon.icons()?;
+        let replaced = self
+            .data_entry(&ResourceEntryName::ID(RT_GROUP_ICON as u32), name, language, "group icon")?
+            .map(|group| icon_directory_entries(group.data()))
+            .transpose()?;
+
+        let icon_table =
+            self.root.table_or_insert(ResourceEntryName::ID(RT_ICON as u32), "icon table")?;
+        let first_free_icon_id = icon_table.next_free_ids(icons.len(), "icon table")?;
+
+        let mut group = Vec::new();
+        group.extend(
+            IconDirectory {
+                reserved: 0,
+                type_:    1,
+                count:    icons.len() as u16,
+            }
+            .as_bytes(),
+        );
+        for (i, icon) in icons.iter().enumerate() {
+            let id = first_free_icon_id + i as u16;
+            let mut entry = read::<IconDirectoryEntry>(&icon[..14])?;
+            entry.id = id;
+            entry.bytes = (icon.len() - 14) as u32;
+            group.extend(entry.as_bytes());
+            let mut inner_table = ResourceTable::default();
+            inner_table.insert(
+                ResourceEntryName::ID(language as u32),
+                ResourceEntry::Data(ResourceData {
+                    data:     icon[14..].to_vec().into(),
+                    codepage: CODE_PAGE_ID_EN_US as u32,
+                    reserved: 0,
+                }),
+            );
+            icon_table.insert(ResourceEntryName::ID(id as u32), ResourceEntry::Table(inner_table));
+        }
+
+        // replace the group in place and remove the replaced images afterwards
+        self.set_data_entry(
+            &ResourceEntryName::ID(RT_GROUP_ICON as u32),
+            name,
+            language,
+            group,
+            "group icon",
+        )?;
+        if let Some(replaced) = replaced {
+            self.remove_group_members(
+                RT_GROUP_ICON,
+                RT_ICON,
+                replaced.iter().map(|entry| entry.id),
+                "icon",
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Remove an icon group and all icon images uniquely referenced by it.
+    ///
+    /// # Returns
+    /// Returns an error if the icon resource directories are invalid.
+    pub fn remove_icon<N: Borrow<ResourceEntryName>>(
+        &mut self, name: N, language: LANGID,
+    ) -> Result<(), ResourceError> {
+        self.remove_group(RT_GROUP_ICON, RT_ICON, name.borrow(), language, "icon")
+    }
+
+    /// Get an icon group as the content of an `.ico` file.
+    ///
+    /// # Returns
+    /// Returns `None` if the icon group does not exist.
+    /// Returns an error if the icon resource directories are invalid.
+    pub fn get_icon_file<N: Borrow<ResourceEntryName>>(
+        &self, name: N, language: LANGID,
+    ) -> Result<Option<Vec<u8>>, ResourceError> {
+        let group = self.data_entry(
+            &ResourceEntryName::ID(RT_GROUP_ICON as u32),
+            name.borrow(),
+            language,
+            "group icon",
+        )?;
+        let group = match group {
+            Some(group) => group,
+            None => return Ok(None),
+        };
+        let mut icons = Vec::new();
+        for mut entry in icon_directory_entries(group.data())? {
+            let data = self.group_member(RT_ICON, entry.id, language, "icon")?;
+            entry.bytes = data.len() as u32;
+            let mut icon = Vec::with_capacity(size_of::<IconDirectoryEntry>() + data.len());
+            icon.extend(entry.as_bytes());
+            icon.extend(data);
+            icons.push(icon);
+        }
+        Ok(Some(build_icon_file(&icons)))
+    }
+
+    /// Get the main icon group of the executable as the content of an `.ico` file.
+    /// The main icon group is the group with the `MAINICON` name, or the first group if it does not exist.
+    ///
+    /// # Returns
+    /// Returns `None` if the executable has no icon group.
+    /// Returns an error if the icon resource directories are invalid.
+    pub fn get_main_icon_file(&self) -> Result<Option<Vec<u8>>, ResourceError> {
+        match self.main_icon_group()? {
+            Some((name, language)) => self.get_icon_file(name, language),
+            None => Ok(None),
+        }
+    }
+
+    #[cfg(feature = "images")]
+    /// Get the decoded images of an icon group.
+    ///
+    /// # Returns
+    /// Returns `None` if the icon group does not exist.
+    /// Returns an error if the icon resource directories are invalid or an icon image could not be decoded.
+    pub fn get_icon_images<N: Borrow<ResourceEntryName>>(
+        &self, name: N, language: LANGID,
+    ) -> Result<Option<Vec<DynamicImage>>, ResourceError> {
+        match self.get_icon_file(name, language)? {
+            Some(data) => Ok(Some(decode_icon_file(&data)?)),
+            None => Ok(None),
+        }
+    }
+
+    #[cfg(feature = "images")]
+    /// Get the decoded images of the main icon group of the executable.
+    ///
+    /// # Returns
+    /// Returns `None` if the executable has no icon group.
+    /// Returns an error if the icon resource directories are invalid or an icon image could not be decoded.
+    pub fn get_main_icon_images(&self) -> Result<Option<Vec<DynamicImage>>, ResourceError> {
+        match self.get_main_icon_file()? {
+            Some(data) => Ok(Some(decode_icon_file(&data)?)),
+            None => Ok(None),
+        }
+    }
+
+    /// Returns the name and language of the main icon group.
+    pub(crate) fn main_icon_group(
+        &self,
+    ) -> Result<Option<(ResourceEntryName, LANGID)>, ResourceError> {
+        let group_table = match self
+            .root
+            .table(ResourceEntryName::ID(RT_GROUP_ICON as u32), "group icon table")?
+        {
+            Some(table) => table,
+            None => return Ok(None),
+        };
+        let main_icon = ResourceEntryName::from_string("MAINICON");
+        let (name, inner_table) = match group_table.entries.get_key_value(&main_icon) {
+            Some(entry) => entry,
+            None => match group_table.entries.first() {
+                Some(entry) => entry,
+                None => return Ok(None),
+            },
+        };
+        let inner_table = inner_table.as_table().ok_or_else(|| {
+            ResourceError::InvalidTable("inner group icon table is not a table".into())
+        })?;
+        Ok(inner_table.entries.keys().find_map(|language| match language {
+            ResourceEntryName::ID(language) => Some((name.clone(), *language as LANGID)),
+            ResourceEntryName::Name(_) => None,
+        }))
+    }
+
+    /// Remove all icon and cursor images that are not referenced by any icon or cursor group.
+    ///
+    /// When `compact` is set, the remaining images are renumbered with consecutive IDs starting at 1,
+    /// and the references in all groups are updated accordingly.
+    ///
+    /// # Returns
+    /// Returns the names of the removed images.
+    /// Returns an error if the icon or cursor resource directories are invalid.
+    pub fn remove_unreferenced_images(
+        &mut self, compact: bool,
+    ) -> Result<RemovedImages, ResourceError> {
+        Ok(RemovedImages {
+            icons:   self.remove_unreferenced_group_members(
+                RT_GROUP_ICON,
+                RT_ICON,
+                compact,
+                "icon",
+            )?,
+            cursors: self.remove_unreferenced_group_members(
+                RT_GROUP_CURSOR,
+                RT_CURSOR,
+                compact,
+                "cursor",
+            )?,
+        })
+    }
+
+    fn remove_unreferenced_group_members(
+        &mut self, group_type: WORD, type_: WORD, compact: bool, kind: &str,
+    ) -> Result<Vec<ResourceEntryName>, ResourceError> {
+        // collect the images referenced by all groups
+        let mut referenced = IndexSet::with_hasher(RandomState::default());
+        if let Some(group_table) = self
+            .root
+            .table(ResourceEntryName::ID(group_type as u32), &format!("group {} table", kind))?
+        {
+            for (_, inner_table) in group_table.entries.iter() {
+                let inner_table = match inner_table.as_table() {
+                    Some(table) => table,
+                    None => continue,
+                };
+                for (_, entry) in inner_table.entries.iter() {
+                    if let Some(data) = entry.as_data() {
+                        for entry in icon_directory_entries(data.data())? {
+                            referenced.insert(entry.id as u32);
+                        }
+                    }
+                }
+            }
+        }
+
+        let table = match self
+            .root
+            .table_mut(ResourceEntryName::ID(type_ as u32), &format!("{} table", kind))?
+        {
+            Some(table) => table,
+            None => return Ok(Vec::new()),
+        };
+
+        // remove the images not referenced by any group
+        let removed = table
+            .entries
+            .keys()
+            .filter(|name| match name {
+                ResourceEntryName::ID(id) => !referenced.contains(id),
+                ResourceEntryName::Name(_) => true,
+            })
+            .cloned()
+            .collect::<Vec<_>>();
+        for name in &removed {
+            table.remove(name);
+        }
+        if table.entries.is_empty() {
+            self.root.remove(ResourceEntryName::ID(type_ as u32));
+            return Ok(removed);
+        }
+        if !compact {
+            return Ok(removed);
+        }
+
+        // renumber the remaining images in the order of their IDs
+        // all remaining images have a 16-bit ID as only those can be referenced by groups
+        let mut old_ids = table
+            .entries
+            .keys()
+            .filter_map(|name| match name {
+                ResourceEntryName::ID(id) => Some(*id),
+                ResourceEntryName::Name(_) => None,
+            })
+            .collect::<Vec<_>>();
+        old_ids.sort();
+        let mut ids = IndexMap::with_hasher(RandomState::default());
+        for (i, id) in old_ids.into_iter().enumerate() {
+            let (Ok(id), Ok(new_id)) = (u16::try_from(id), u16::try_from(i + 1)) else {
+                return Err(ResourceError::InvalidTable(format!(
+                    "{} table has more images than 16-bit ids",
+                    kind
+                )));
+            };
+            ids.insert(id, new_id);
+        }
+        let mut entries = core::mem::take(&mut table.entries)
+            .into_iter()
+            .filter_map(|(name, entry)| match name {
+                ResourceEntryName::ID(id) => Some((id, entry)),
+                ResourceEntryName::Name(_) => None,
+            })
+            .collect::<Vec<_>>();
+        entries.sort_by_key(|(id, _)| *id);
+        table.data.number_of_name_entries = 0;
+        table.data.number_of_id_entries = 0;
+        for (i, (_, entry)) in entries.into_iter().enumerate() {
+            table.insert(ResourceEntryName::ID(i as u32 + 1), entry);
+        }
+
+        // update the references in all groups
+        if let Some(group_table) = self
+            .root
+            .table_mut(ResourceEntryName::ID(group_type as u32), &format!("group {} table", kind))?
+        {
+            for (_, inner_table) in group_table.entries.iter_mut() {
+                let inner_table = match inner_table.as_table_mut() {
+                    Some(table) => table,
+                    None => continue,
+                };
+                for (_, entry) in inner_table.entries.iter_mut() {
+                    if let Some(data) = entry.as_data_mut() {
+                        let mut group = data.data().to_vec();
+                        for (i, entry) in icon_directory_entries(data.data())?.iter().enumerate() {
+                            let offset = 6 + i * size_of::<IconDirectoryEntry>() + 12;
+                            if let Some(&id) = ids.get(&entry.id) {
+                                group[offset..offset + 2].copy_from_slice(&id.to_le_bytes());
+                            }
+                        }
+                        data.set_data(group);
+                    }
+                }
+            }
+        }
+
+        Ok(removed)
+    }
+
+    /// Remove an icon or cursor group and all images uniquely referenced by it.
+    /// The `kind` is used to describe the resource in the returned error.
+    pub(crate) fn remove_group(
+        &mut self, group_type: WORD, type_: WORD, name: &ResourceEntryName, language: LANGID,
+        kind: &str,
+    ) -> Result<(), ResourceError> {
+        let group = self.remove_data_entry(
+            &ResourceEntryName::ID(group_type as u32),
+            name,
+            language,
+            &format!("group {}", kind),
+        )?;
+        let group = match group {
+            Some(group) => group,
+            None => return Ok(()),
+        };
+        let ids = icon_directory_entries(group.data())?.into_iter().map(|entry| entry.id);
+        self.remove_group_members(group_type, type_, ids, kind)
+    }
+
+    /// Remove the images with the given IDs that are not referenced by any icon or cursor group.
+    /// The `kind` is used to describe the resource in the returned error.
+    pub(crate) fn remove_group_members<I: IntoIterator<Item = u16>>(
+        &mut self, group_type: WORD, type_: WORD, ids: I, kind: &str,
+    ) -> Result<(), ResourceError> {
+        // get a list of all images in the group for removal
+        let mut images_to_remove = IndexSet::with_hasher(RandomState::default());
+        images_to_remove.extend(ids);
+
+        // remove images referenced by other groups from the list
+        if let Some(group_table) = self
+            .root
+            .table(ResourceEntryName::ID(group_type as u32), &format!("group {} table", kind))?
+        {
+            for (_, inner_table) in group_table.entries.iter() {
+                let inner_table = match inner_table.as_table() {
+                    Some(table) => table,
+                    None => continue,
+                };
+                for (_, entry) in inner_table.entries.iter() {
+                    if let Some(data) = entry.as_data() {
+                        for entry in icon_directory_entries(data.data())? {
+                            images_to_remove.swap_remove(&entry.id);
+                        }
+                    }
+                }
+            }
+        }
+
+        // remove the images from the image table
+        if let Some(table) = self
+            .root
+            .table_mut(ResourceEntryName::ID(type_ as u32), &format!("{} table", kind))?
+        {
+            for id in images_to_remove {
+                table.remove(ResourceEntryName::ID(id as u32));
+            }
+            if table.entries.is_empty() {
+                self.root.remove(ResourceEntryName::ID(type_ as u32));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Get the data of an icon or cursor image referenced by a group.
+    /// The image in the language of the group is preferred, otherwise the first language is used.
+    pub(crate) fn group_member(
+        &self, type_: WORD, id: u16, language: LANGID, kind: &str,
+    ) -> Result<&[u8], ResourceError> {
+        let inner_table = self
+            .root
+            .table(ResourceEntryName::ID(type_ as u32), &format!("{} table", kind))?
+            .map(|table| {
+                table.table(ResourceEntryName::ID(id as u32), &format!("inner {} table", kind))
+            })
+            .transpose()?
+            .flatten()
+            .ok_or_else(|| {
+                ResourceError::InvalidTable(format!("{} {} does not exist", kind, id))
+            })?;
+        inner_table
+            .get(ResourceEntryName::ID(language as u32))
+            .or_else(|| inner_table.entries.first().map(|(_, v)| v))
+            .and_then(|entry| entry.as_data())
+            .map(|data| data.data())
+            .ok_or_else(|| ResourceError::InvalidTable(format!("{} table entry is not data", kind)))
+    }
+}
+
+/// Read the entries of an icon group directory.
+/// Cursor group directories have the same layout and can be read as well.
+pub(crate) fn icon_directory_entries(data: &[u8]) -> Result<Vec<IconDirectoryEntry>, ReadError> {
+    let directory = read::<IconDirectory>(data)?;
+    (0..directory.count as usize)
+        .map(|i| {
+            read::<IconDirectoryEntry>(
+                data.get(6 + i * size_of::<IconDirectoryEntry>()..).unwrap_or_default(),
+            )
+        })
+        .collect()
+}
+
+#[cfg(feature = "images")]
+/// Decode all images of an `.ico` file.
+fn decode_icon_file(data: &[u8]) -> Result<Vec<DynamicImage>, ResourceError> {
+    use image::ImageFormat;
+    // the icon decoder only decodes the best image, so each image is decoded as a separate icon file
+    data.icons()?
+        .into_iter()
+        .map(|icon| {
+            let data = build_icon_file(&[icon]);
+            Ok(image::load_from_memory_with_format(&data, ImageFormat::Ico)?)
+        })
+        .collect()
+}
+
+#[cfg(feature = "images")]
+/// Image format of an icon image.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub enum IconFormat {
+    /// PNG image, supported since Windows Vista.
+    Png,
+    /// Device-independent bitmap with an AND mask, supported by all Windows versions.
+    Bitmap,
+}
+
+#[cfg(feature = "images")]
+/// Options for converting an image to an icon.
+///
+/// The default options create 32-bit PNG images with the sizes 256, 128, 48, 32, 24 and 16 using the Lanczos3 filter,
+/// which is the conversion used when setting a [`DynamicImage`] as an icon.
+#[derive(Debug, Clone, PartialEq)]
+pub struct IconOptions {
+    sizes:     Vec<(u32, IconFormat)>,
+    filter:    FilterType,
+    bit_depth: u16,
+}
+#[cfg(feature = "images")]
+impl Default for IconOptions {
+    fn default() -> Self {
+        Self {
+            sizes:     [256, 128, 48, 32, 24, 16].map(|size| (size, IconFormat::Png)).to_vec(),
+            filter:    FilterType::Lanczos3,
+            bit_depth: 32,
+        }
+    }
+}
+#[cfg(feature = "images")]
+impl IconOptions {
+    /// Create the default icon options.
+    pub fn new() -> Self { Self::default() }
+
+    /// Set the sizes of the icon images in pixels, replacing the current sizes.
+    /// The images are stored as PNG images unless changed with [`format`](Self::format) or [`png_from`](Self::png_from).
+    pub fn sizes(mut self, sizes: &[u32]) -> Self {
+        self.sizes = sizes.iter().map(|&size| (size, IconFormat::Png)).collect();
+        self
+    }
+
+    /// Set the image format of a size, adding the size if it does not exist.
+    pub fn format(mut self, size: u32, format: IconFormat) -> Self {
+        match self.sizes.iter_mut().find(|(s, _)| *s == size) {
+            Some((_, f)) => *f = format,
+            None => self.sizes.push((size, format)),
+        }
+        self
+    }
+
+    /// Store all sizes from `min_size` upwards as PNG images and all smaller sizes as bitmaps.
+    pub fn png_from(mut self, min_size: u32) -> Self {
+        for (size, format) in self.sizes.iter_mut() {
+            *format = if *size >= min_size {
+                IconFormat::Png
+            } else {
+                IconFormat::Bitmap
+            };
+        }
+        self
+    }
+
+    /// Set the filter used for resizing the image.
+    pub fn filter(mut self, filter: FilterType) -> Self {
+        self.filter = filter;
+        self
+    }
+
+    /// Set the bit depth of the icon images, either 24 or 32 bits.
+    /// With 24 bits, transparency is only stored in the AND mask of bitmaps and discarded for PNG images.
+    pub fn bit_depth(mut self, bit_depth: u16) -> Self {
+        self.bit_depth = bit_depth;
+        self
+    }
+
+    /// Convert an image to the content of an `.ico` file.
+    ///
+    /// # Returns
+    /// Returns an error if a size or the bit depth is not supported or the image could not be encoded.
+    pub fn encode(&self, image: &DynamicImage) -> Result<Vec<u8>, ResourceError> {
+        Ok(build_icon_file(&self.icons(image)?))
+    }
+
+    /// Complete the content of an `.ico` file with the sizes it is missing.
+    /// All images of the icon are kept, and each missing size is resized from the best-matching image,
+    /// which is the smallest image not smaller than the missing size, or the largest image otherwise.
+    ///
+    /// # Returns
+    /// Returns an error if the icon is invalid, an image could not be decoded or a size or the bit depth is not supported.
+    pub fn complete(&self, icon: &[u8]) -> Result<Vec<u8>, ResourceError> {
+        let mut icons = icon.icons()?;
+        let size = |icon: &Vec<u8>| if icon[0] == 0 { 256 } else { icon[0] as u32 };
+        let mut missing = Vec::new();
+        for &(target, format) in &self.sizes {
+            if icons.iter().any(|icon| size(icon) == target) {
+                continue;
+            }
+            let source = icons
+                .iter()
+                .filter(|icon| size(icon) >= target)
+                .min_by_key(|icon| {
+                    (size(icon), core::cmp::Reverse(read::<u16>(&icon[6..]).unwrap_or(0)))
+                })
+                .or_else(|| icons.iter().max_by_key(|icon| size(icon)))
+                .ok_or_else(|| ResourceError::InvalidBytes("icon data has no images".into()))?;
+            let image = decode_icon_file(&build_icon_file(core::slice::from_ref(source)))?;
+            let options = Self {
+                sizes: vec![(target, format)],
+                ..self.clone()
+            };
+            missing.extend(options.icons(&image[0])?);
+        }
+        icons.extend(missing);
+        Ok(build_icon_file(&icons))
+    }
+
+    /// Convert an image to the icon data representation of [`ToIcon::icons`].
+    pub(crate) fn icons(&self, image: &DynamicImage) -> Result<Vec<Vec<u8>>, ResourceError> {
+        use image::ImageFormat;
+        use std::io::Cursor;
+        if self.bit_depth != 24 && self.bit_depth != 32 {
+            return Err(ResourceError::InvalidBytes(
+                format!("unsupported icon bit depth: {}", self.bit_depth).into(),
+            ));
+        }
+        self.sizes
+            .iter()
+            .map(|&(size, format)| {
+                if size == 0 || size > 256 {
+                    return Err(ResourceError::InvalidBytes(
+                        format!("unsupported icon size: {}", size).into(),
+                    ));
+                }
+                let image = image.resize_exact(size, size, self.filter).to_rgba8();
+                let mut data = Vec::new();
+                match format {
+                    IconFormat::Png if self.bit_depth == 32 => {
+                        image.write_to(&mut Cursor::new(&mut data), ImageFormat::Png)?
+                    }
+                    IconFormat::Png => DynamicImage::ImageRgba8(image)
+                        .to_rgb8()
+                        .write_to(&mut Cursor::new(&mut data), ImageFormat::Png)?,
+                    IconFormat::Bitmap => data = build_icon_bitmap(&image, self.bit_depth),
+                }
+                // prepend 12 bytes of ICO directory entry metadata + 2-byte dummy id
+                let mut icon = Vec::with_capacity(14 + data.len());
+                icon.extend(
+                    IconDirectoryEntry {
+                        width:       size as u8,
+                        height:      size as u8,
+                        color_count: 0,
+                        reserved:    0,
+                        planes:      1,
+                        bit_count:   self.bit_depth,
+                        bytes:       data.len() as u32,
+                        id:          0,
+                    }
+                    .as_bytes(),
+                );
+                icon.extend(data);
+                Ok(icon)
+            })
+            .collect()
+    }
+}
+
+#[cfg(feature = "images")]
+/// Build the bitmap of an icon image with a 24-bit or 32-bit color bitmap followed by the AND mask.
+fn build_icon_bitmap(image: &image::RgbaImage, bit_count: u16) -> Vec<u8> {
+    use core::iter;
+    let (width, height) = image.dimensions();
+    let color_stride = aligned_to(width as usize * bit_count as usize / 8, 4);
+    let mask_stride = aligned_to((width as usize).div_ceil(8), 4);
+    let size_image = (color_stride + mask_stride) * height as usize;
+
+    let mut data = Vec::with_capacity(size_of::<BitmapInfoHeader>() + size_image);
+    data.extend(
+        BitmapInfoHeader {
+            size: size_of::<BitmapInfoHeader>() as u32,
+            width: width as i32,
+            // the height includes the AND mask
+            height: height as i32 * 2,
+            planes: 1,
+            bit_count,
+            compression: BI_RGB,
+            size_image: size_image as u32,
+            ..Default::default()
+        }
+        .as_bytes(),
+    );
+    // rows are stored bottom-up
+    for row in image.rows().rev() {
+        let start = data.len();
+        for pixel in row {
+            let [r, g, b, a] = pixel.0;
+            data.extend([b, g, r]);
+            if bit_count == 32 {
+                data.push(a);
+            }
+        }
+        data.extend(iter::repeat_n(0, color_stride - (data.len() - start)));
+    }
+    for row in image.rows().rev() {
+        let mut mask = vec![0u8; mask_stride];
+        for (x, pixel) in row.enumerate() {
+            if pixel.0[3] < 0x80 {
+                mask[x / 8] |= 0x80 >> (x % 8);
+            }
+        }
+        data.extend(mask);
+    }
+    data
+}
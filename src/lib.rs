@@ -50,12 +50,27 @@
 
 extern crate alloc;
 
+pub(crate) mod accelerator;
+pub(crate) mod animated;
+pub(crate) mod bitmap;
+pub(crate) mod cursor;
+pub(crate) mod dialog;
 pub(crate) mod errors;
+pub(crate) mod icon;
 pub(crate) mod image;
+pub(crate) mod manifest;
+pub(crate) mod menu;
+pub(crate) mod message_table;
 pub(crate) mod resource;
+pub(crate) mod string_table;
 pub(crate) mod util;
+pub(crate) mod version;
+pub(crate) mod xml;
 
 pub mod constants;
 pub mod types;
 
-pub use crate::{errors::*, image::*, resource::*};
+pub use crate::{
+    accelerator::*, animated::*, bitmap::*, cursor::*, dialog::*, errors::*, icon::*, image::*,
+    manifest::*, menu::*, message_table::*, resource::*, string_table::*, version::*,
+};
@@ -0,0 +1,737 @@
+//! Application manifest resources.
+//!
+//! Application manifests are stored in `RT_MANIFEST` resources as XML documents.
+//! The [`Manifest`] model provides access to the common settings while preserving all other content of the document.
+//! See <https://learn.microsoft.com/en-us/windows/win32/sbscs/application-manifests> for more information.
+
+use alloc::{
+    format,
+    string::{String, ToString},
+    vec::Vec,
+};
+use core::{borrow::Borrow, fmt, str::FromStr};
+
+use log::warn;
+
+use crate::{constants::*, errors::*, resource::*, xml::*};
+
+const NAMESPACE_ASM_V3: &str = "urn:schemas-microsoft-com:asm.v3";
+const NAMESPACE_COMPATIBILITY: &str = "urn:schemas-microsoft-com:compatibility.v1";
+const NAMESPACE_SETTINGS_2005: &str = "http://schemas.microsoft.com/SMI/2005/WindowsSettings";
+const NAMESPACE_SETTINGS_2016: &str = "http://schemas.microsoft.com/SMI/2016/WindowsSettings";
+const NAMESPACE_SETTINGS_2019: &str = "http://schemas.microsoft.com/SMI/2019/WindowsSettings";
+const NAMESPACE_SETTINGS_2020: &str = "http://schemas.microsoft.com/SMI/2020/WindowsSettings";
+
+const COMMON_CONTROLS_NAME: &str = "Microsoft.Windows.Common-Controls";
+
+/// Requested execution level of an application.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub enum ExecutionLevel {
+    AsInvoker,
+    HighestAvailable,
+    RequireAdministrator,
+}
+impl ExecutionLevel {
+    /// Returns the value of the `level` attribute.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ExecutionLevel::AsInvoker => "asInvoker",
+            ExecutionLevel::HighestAvailable => "highestAvailable",
+            ExecutionLevel::RequireAdministrator => "requireAdministrator",
+        }
+    }
+}
+impl FromStr for ExecutionLevel {
+    type Err = ReadError;
+
+    fn from_str(level: &str) -> Result<Self, Self::Err> {
+        match level {
+            "asInvoker" => Ok(ExecutionLevel::AsInvoker),
+            "highestAvailable" => Ok(ExecutionLevel::HighestAvailable),
+            "requireAdministrator" => Ok(ExecutionLevel::RequireAdministrator),
+            level => Err(ReadError(format!("invalid execution level: {}", level))),
+        }
+    }
+}
+
+/// Assembly identity of an application or a dependency.
+#[derive(Debug, Clone, Eq, PartialEq, Default)]
+pub struct AssemblyIdentity {
+    pub type_:                  Option<String>,
+    pub name:                   Option<String>,
+    pub version:                Option<String>,
+    pub processor_architecture: Option<String>,
+    pub public_key_token:       Option<String>,
+    pub language:               Option<String>,
+}
+impl AssemblyIdentity {
+    const ATTRIBUTES: [&'static str; 6] = [
+        "type",
+        "name",
+        "version",
+        "processorArchitecture",
+        "publicKeyToken",
+        "language",
+    ];
+
+    fn values(&self) -> [&Option<String>; 6] {
+        [
+            &self.type_,
+            &self.name,
+            &self.version,
+            &self.processor_architecture,
+            &self.public_key_token,
+            &self.language,
+        ]
+    }
+
+    fn from_element(element: &XmlElement) -> Self {
+        let [
+            type_,
+            name,
+            version,
+            processor_architecture,
+            public_key_token,
+            language,
+        ] = Self::ATTRIBUTES.map(|attribute| element.attribute(attribute));
+        Self {
+            type_,
+            name,
+            version,
+            processor_architecture,
+            public_key_token,
+            language,
+        }
+    }
+
+    fn write_element(&self, element: &mut XmlElement) {
+        for (attribute, value) in Self::ATTRIBUTES.iter().zip(self.values()) {
+            match value {
+                Some(value) => element.set_attribute(attribute, value),
+                None => element.remove_attribute(attribute),
+            }
+        }
+    }
+}
+
+/// Characters of the bytes `0x80` to `0x9F` in windows-1252.
+/// The five undefined bytes are mapped to the C1 control characters of the same value.
+const WINDOWS_1252: [char; 32] = [
+    '\u{20AC}', '\u{0081}', '\u{201A}', '\u{0192}', '\u{201E}', '\u{2026}', '\u{2020}', '\u{2021}',
+    '\u{02C6}', '\u{2030}', '\u{0160}', '\u{2039}', '\u{0152}', '\u{008D}', '\u{017D}', '\u{008F}',
+    '\u{0090}', '\u{2018}', '\u{2019}', '\u{201C}', '\u{201D}', '\u{2022}', '\u{2013}', '\u{2014}',
+    '\u{02DC}', '\u{2122}', '\u{0161}', '\u{203A}', '\u{0153}', '\u{009D}', '\u{017E}', '\u{0178}',
+];
+
+/// Text encoding of a manifest resource.
+///
+/// The encoding is detected from the byte order mark, the bytes of the first character and the `encoding` of the XML declaration.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, Default)]
+pub enum ManifestEncoding {
+    #[default]
+    Utf8,
+    Utf8Bom,
+    Utf16LE,
+    Utf16LEBom,
+    Utf16BE,
+    Utf16BEBom,
+    /// ISO-8859-1, where every byte is the character of the same value.
+    Latin1,
+    /// Windows code page 1252, which differs from ISO-8859-1 in the bytes `0x80` to `0x9F`.
+    Windows1252,
+}
+impl ManifestEncoding {
+    /// Detect the encoding of manifest data.
+    ///
+    /// A byte order mark takes precedence.
+    /// Without one, a first character with a zero high or low byte indicates UTF-16 in little or big endian,
+    /// as described in <https://www.w3.org/TR/xml/#sec-guessing>.
+    /// Otherwise the data uses the `encoding` of the XML declaration, or UTF-8 if there is none.
+    ///
+    /// # Returns
+    /// Returns an error if the XML declaration names an unsupported encoding.
+    pub fn detect(data: &[u8]) -> Result<Self, ReadError> {
+        let encoding = match data {
+            [0xEF, 0xBB, 0xBF, ..] => return Ok(ManifestEncoding::Utf8Bom),
+            [0xFF, 0xFE, ..] => return Ok(ManifestEncoding::Utf16LEBom),
+            [0xFE, 0xFF, ..] => return Ok(ManifestEncoding::Utf16BEBom),
+            [first, 0, ..] if *first != 0 => ManifestEncoding::Utf16LE,
+            [0, second, ..] if *second != 0 => ManifestEncoding::Utf16BE,
+            _ => ManifestEncoding::Utf8,
+        };
+
+        // the declaration is ASCII, so it can be read from the start of the data in the detected width
+        let width = if encoding == ManifestEncoding::Utf8 {
+            1
+        } else {
+            2
+        };
+        let declaration = data
+            .chunks(width)
+            .take(128)
+            .map(|c| c.iter().fold(0u8, |a, b| a | b) as char)
+            .collect::<String>();
+        let declared = match declared_encoding(&declaration) {
+            Some(declared) => declared.to_ascii_uppercase(),
+            None => return Ok(encoding),
+        };
+        let (declared_encoding, declared_width) = match declared.as_str() {
+            "UTF-8" | "US-ASCII" | "ASCII" => (ManifestEncoding::Utf8, 1),
+            "UTF-16" | "UTF-16LE" | "UTF-16BE" | "UCS-2" => (encoding, 2),
+            "ISO-8859-1" | "ISO_8859-1" | "LATIN1" | "LATIN-1" | "L1" => {
+                (ManifestEncoding::Latin1, 1)
+            }
+            "WINDOWS-1252" | "CP1252" => (ManifestEncoding::Windows1252, 1),
+            _ => return Err(ReadError(format!("unsupported manifest encoding: {}", declared))),
+        };
+        if declared_width != width {
+            warn!("manifest declares encoding {} but is encoded as {:?}", declared, encoding);
+            return Ok(encoding);
+        }
+        Ok(declared_encoding)
+    }
+
+    /// Returns the length of the byte order mark.
+    fn bom_len(&self) -> usize {
+        match self {
+            ManifestEncoding::Utf8Bom => 3,
+            ManifestEncoding::Utf16LEBom | ManifestEncoding::Utf16BEBom => 2,
+            _ => 0,
+        }
+    }
+
+    /// Decode manifest data with this encoding.
+    /// The byte order mark is not part of the returned string.
+    ///
+    /// # Returns
+    /// Returns an error if the data is not valid in this encoding, including UTF-16 data with an odd length,
+    /// so that encoding the returned string reproduces the data.
+    pub fn decode(&self, data: &[u8]) -> Result<String, ReadError> {
+        let data = data.get(self.bom_len()..).unwrap_or_default();
+        let units = match self {
+            ManifestEncoding::Utf8 | ManifestEncoding::Utf8Bom => {
+                return String::from_utf8(data.to_vec())
+                    .map_err(|_| ReadError("manifest is not valid utf-8".into()));
+            }
+            ManifestEncoding::Latin1 => return Ok(data.iter().map(|b| *b as char).collect()),
+            ManifestEncoding::Windows1252 => {
+                return Ok(data
+                    .iter()
+                    .map(|b| match b {
+                        0x80..=0x9F => WINDOWS_1252[(b - 0x80) as usize],
+                        _ => *b as char,
+                    })
+                    .collect());
+            }
+            _ if data.len() % 2 != 0 => {
+                return Err(ReadError("utf-16 manifest has an odd length".into()));
+            }
+            ManifestEncoding::Utf16LE | ManifestEncoding::Utf16LEBom => data
+                .chunks_exact(2)
+                .map(|c| u16::from_le_bytes([c[0], c[1]]))
+                .collect::<Vec<_>>(),
+            ManifestEncoding::Utf16BE | ManifestEncoding::Utf16BEBom => data
+                .chunks_exact(2)
+                .map(|c| u16::from_be_bytes([c[0], c[1]]))
+                .collect::<Vec<_>>(),
+        };
+        String::from_utf16(&units).map_err(|_| ReadError("manifest is not valid utf-16".into()))
+    }
+
+    /// Encode a manifest with this encoding, including the byte order mark if any.
+    /// Characters that ISO-8859-1 and windows-1252 can't represent are written as XML character references.
+    pub fn encode(&self, manifest: &str) -> Vec<u8> {
+        match self {
+            ManifestEncoding::Latin1 | ManifestEncoding::Windows1252 => {
+                let mut data = Vec::with_capacity(manifest.len());
+                for c in manifest.chars() {
+                    let byte = match c as u32 {
+                        byte @ (0x00..=0x7F | 0xA0..=0xFF) => Some(byte as u8),
+                        byte @ 0x80..=0x9F if *self == ManifestEncoding::Latin1 => Some(byte as u8),
+                        _ if *self == ManifestEncoding::Windows1252 => WINDOWS_1252
+                            .iter()
+                            .position(|w| *w == c)
+                            .map(|index| index as u8 + 0x80),
+                        _ => None,
+                    };
+                    match byte {
+                        Some(byte) => data.push(byte),
+                        None => data.extend(format!("&#x{:X};", c as u32).bytes()),
+                    }
+                }
+                data
+            }
+            ManifestEncoding::Utf8 => manifest.as_bytes().to_vec(),
+            ManifestEncoding::Utf8Bom => [&[0xEF, 0xBB, 0xBF], manifest.as_bytes()].concat(),
+            ManifestEncoding::Utf16LE | ManifestEncoding::Utf16LEBom => {
+                let bom = (*self == ManifestEncoding::Utf16LEBom).then_some(0xFEFF);
+                bom.into_iter()
+                    .chain(manifest.encode_utf16())
+                    .flat_map(u16::to_le_bytes)
+                    .collect()
+            }
+            ManifestEncoding::Utf16BE | ManifestEncoding::Utf16BEBom => {
+                let bom = (*self == ManifestEncoding::Utf16BEBom).then_some(0xFEFF);
+                bom.into_iter()
+                    .chain(manifest.encode_utf16())
+                    .flat_map(u16::to_be_bytes)
+                    .collect()
+            }
+        }
+    }
+}
+
+/// Returns the value of the `encoding` pseudo-attribute of the XML declaration at the start of a document.
+fn declared_encoding(manifest: &str) -> Option<&str> {
+    let declaration = manifest.strip_prefix("<?xml")?;
+    let declaration = &declaration[..declaration.find("?>")?];
+    let value = declaration
+        .split_once("encoding")?
+        .1
+        .trim_start()
+        .strip_prefix('=')?
+        .trim_start();
+    let quote = value.chars().next().filter(|c| *c == '"' || *c == '\'')?;
+    value[1..].split(quote).next()
+}
+
+/// Application manifest.
+/// This is the content of an `RT_MANIFEST` resource.
+///
+/// The manifest keeps the original formatting and all content that is not modified through the setters.
+/// Elements are matched by their local name regardless of the namespace prefix.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Manifest {
+    nodes: Vec<XmlNode>,
+}
+impl Default for Manifest {
+    fn default() -> Self {
+        Self::parse(concat!(
+            "<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"yes\"?>\n",
+            "<assembly xmlns=\"urn:schemas-microsoft-com:asm.v1\" manifestVersion=\"1.0\">\n",
+            "</assembly>\n"
+        ))
+        .unwrap()
+    }
+}
+impl Manifest {
+    /// Parse a manifest from an XML document.
+    ///
+    /// # Returns
+    /// Returns an error if the document is not well-formed or the root element is not an `assembly` element.
+    pub fn parse(manifest: &str) -> Result<Self, ReadError> {
+        let nodes = parse(manifest)?;
+        let mut elements = nodes.iter().filter(|node| matches!(node, XmlNode::Element(_)));
+        match (elements.next(), elements.next()) {
+            (Some(XmlNode::Element(root)), None) if root.local_name() == "assembly" => {
+                Ok(Self { nodes })
+            }
+            _ => Err("manifest root element is not an assembly element".into()),
+        }
+    }
+
+    /// Build the manifest into an XML document.
+    pub fn build(&self) -> String {
+        let mut manifest = String::new();
+        write(&self.nodes, &mut manifest);
+        manifest
+    }
+
+    fn assembly(&self) -> &XmlElement {
+        self.nodes
+            .iter()
+            .find_map(|node| match node {
+                XmlNode::Element(element) => Some(element),
+                _ => None,
+            })
+            .unwrap()
+    }
+
+    fn assembly_mut(&mut self) -> &mut XmlElement {
+        self.nodes
+            .iter_mut()
+            .find_map(|node| match node {
+                XmlNode::Element(element) => Some(element),
+                _ => None,
+            })
+            .unwrap()
+    }
+
+    /// Returns the element at the given path of local names below the assembly element.
+    fn element(&self, path: &[&str]) -> Option<&XmlElement> {
+        path.iter().try_fold(self.assembly(), |element, name| element.child(name))
+    }
+
+    /// Returns the mutable element at the given path below the assembly element, creating missing elements.
+    /// Each path entry contains the local name and the namespace of a created element.
+    fn element_or_insert(&mut self, path: &[(&str, &str)]) -> &mut XmlElement {
+        let mut element = self.assembly_mut();
+        for (depth, (name, namespace)) in path.iter().enumerate() {
+            // elements without a namespace inherit the namespace prefix of the parent
+            let child = match element.elements().any(|e| e.local_name() == *name) {
+                true => None,
+                false if namespace.is_empty() => Some(element.new_child(name)),
+                false => Some(XmlElement::with_namespace(name, namespace)),
+            };
+            if let Some(child) = child {
+                element.append_child(child, depth + 1);
+            }
+            element = element.child_mut(name).unwrap();
+        }
+        element
+    }
+
+    /// Remove the element at the given path below the assembly element.
+    /// Parent elements along the path that become empty are removed as well.
+    fn remove_element(&mut self, path: &[&str]) {
+        fn remove(element: &mut XmlElement, path: &[&str]) {
+            let (name, rest) = match path.split_first() {
+                Some(split) => split,
+                None => return,
+            };
+            if !rest.is_empty() {
+                if let Some(child) = element.child_mut(name) {
+                    remove(child, rest);
+                }
+            }
+            element.remove_children(|child| {
+                child.local_name() == *name
+                    && (rest.is_empty()
+                        || (child.elements().next().is_none() && child.text().trim().is_empty()))
+            });
+        }
+        remove(self.assembly_mut(), path);
+    }
+
+    /// Returns the requested execution level.
+    pub fn execution_level(&self) -> Option<ExecutionLevel> {
+        self.element(&[
+            "trustInfo",
+            "security",
+            "requestedPrivileges",
+            "requestedExecutionLevel",
+        ])?
+        .attribute("level")?
+        .parse()
+        .ok()
+    }
+
+    /// Set the requested execution level.
+    /// Setting `None` removes the requested execution level including the UI access flag.
+    pub fn set_execution_level(&mut self, level: Option<ExecutionLevel>) {
+        match level {
+            Some(level) => {
+                self.requested_execution_level_mut().set_attribute("level", level.as_str())
+            }
+            None => self.remove_element(&[
+                "trustInfo",
+                "security",
+                "requestedPrivileges",
+                "requestedExecutionLevel",
+            ]),
+        }
+    }
+
+    /// Returns if the application requests UI access.
+    pub fn ui_access(&self) -> bool {
+        self.element(&[
+            "trustInfo",
+            "security",
+            "requestedPrivileges",
+            "requestedExecutionLevel",
+        ])
+        .and_then(|element| element.attribute("uiAccess"))
+        .is_some_and(|ui_access| ui_access.eq_ignore_ascii_case("true"))
+    }
+
+    /// Set if the application requests UI access.
+    /// This adds the `asInvoker` execution level if no execution level is requested.
+    pub fn set_ui_access(&mut self, ui_access: bool) {
+        let element = self.requested_execution_level_mut();
+        element.set_attribute("uiAccess", if ui_access { "true" } else { "false" });
+    }
+
+    fn requested_execution_level_mut(&mut self) -> &mut XmlElement {
+        let element = self.element_or_insert(&[
+            ("trustInfo", NAMESPACE_ASM_V3),
+            ("security", ""),
+            ("requestedPrivileges", ""),
+            ("requestedExecutionLevel", ""),
+        ]);
+        if element.attribute("level").is_none() {
+            element.set_attribute("level", ExecutionLevel::AsInvoker.as_str());
+        }
+        element
+    }
+
+    fn windows_setting(&self, name: &str) -> Option<String> {
+        self.element(&["application", "windowsSettings", name])
+            .map(|element| element.text().trim().to_string())
+    }
+
+    fn set_windows_setting(&mut self, name: &str, namespace: &str, value: Option<&str>) {
+        match value {
+            Some(value) => self
+                .element_or_insert(&[
+                    ("application", NAMESPACE_ASM_V3),
+                    ("windowsSettings", ""),
+                    (name, namespace),
+                ])
+                .set_text(value),
+            None => self.remove_element(&["application", "windowsSettings", name]),
+        }
+    }
+
+    /// Returns the legacy DPI awareness setting, such as `true` or `true/pm`.
+    pub fn dpi_aware(&self) -> Option<String> { self.windows_setting("dpiAware") }
+
+    /// Set the legacy DPI awareness setting.
+    /// Setting `None` removes the setting.
+    pub fn set_dpi_aware(&mut self, dpi_aware: Option<&str>) {
+        self.set_windows_setting("dpiAware", NAMESPACE_SETTINGS_2005, dpi_aware)
+    }
+
+    /// Returns the DPI awareness setting, such as `PerMonitorV2` or a comma-separated list of fallbacks.
+    pub fn dpi_awareness(&self) -> Option<String> { self.windows_setting("dpiAwareness") }
+
+    /// Set the DPI awareness setting, which takes precedence over the legacy DPI awareness setting since Windows 10 1607.
+    /// Setting `None` removes the setting.
+    pub fn set_dpi_awareness(&mut self, dpi_awareness: Option<&str>) {
+        self.set_windows_setting("dpiAwareness", NAMESPACE_SETTINGS_2016, dpi_awareness)
+    }
+
+    /// Returns if the application supports paths longer than `MAX_PATH`.
+    pub fn long_path_aware(&self) -> Option<bool> {
+        self.windows_setting("longPathAware")
+            .map(|value| value.eq_ignore_ascii_case("true"))
+    }
+
+    /// Set if the application supports paths longer than `MAX_PATH`.
+    /// Setting `None` removes the setting.
+    pub fn set_long_path_aware(&mut self, long_path_aware: Option<bool>) {
+        let value = long_path_aware.map(|value| if value { "true" } else { "false" });
+        self.set_windows_setting("longPathAware", NAMESPACE_SETTINGS_2016, value)
+    }
+
+    /// Returns the active code page setting, such as `UTF-8`.
+    pub fn active_code_page(&self) -> Option<String> { self.windows_setting("activeCodePage") }
+
+    /// Set the active code page setting.
+    /// Use [`MANIFEST_CODE_PAGE_UTF8`](crate::constants::MANIFEST_CODE_PAGE_UTF8) to use UTF-8 as the process code page.
+    /// Setting `None` removes the setting.
+    pub fn set_active_code_page(&mut self, code_page: Option<&str>) {
+        self.set_windows_setting("activeCodePage", NAMESPACE_SETTINGS_2019, code_page)
+    }
+
+    /// Returns the heap type setting, such as `SegmentHeap`.
+    pub fn heap_type(&self) -> Option<String> { self.windows_setting("heapType") }
+
+    /// Set the heap type setting.
+    /// Setting `None` removes the setting.
+    pub fn set_heap_type(&mut self, heap_type: Option<&str>) {
+        self.set_windows_setting("heapType", NAMESPACE_SETTINGS_2020, heap_type)
+    }
+
+    /// Returns the IDs of the supported operating systems.
+    /// See the `SUPPORTED_OS_*` constants for the known IDs.
+    pub fn supported_os(&self) -> Vec<String> {
+        self.element(&["compatibility", "application"])
+            .map(|application| {
+                application
+                    .elements()
+                    .filter(|element| element.local_name() == "supportedOS")
+                    .filter_map(|element| element.attribute("Id"))
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// Set the IDs of the supported operating systems, replacing the existing IDs.
+    /// Other compatibility settings are kept.
+    /// Setting an empty list removes the supported operating systems.
+    pub fn set_supported_os<S: AsRef<str>>(&mut self, ids: &[S]) {
+        if ids.is_empty() {
+            self.remove_element(&["compatibility", "application", "supportedOS"]);
+            return;
+        }
+        let application = self.element_or_insert(&[
+            ("compatibility", NAMESPACE_COMPATIBILITY),
+            ("application", ""),
+        ]);
+        application.remove_children(|element| element.local_name() == "supportedOS");
+        let prefix = &application.name[..application.name.len() - application.local_name().len()];
+        let prefix = prefix.to_string();
+        for id in ids.iter().rev() {
+            let mut element = XmlElement::new(&format!("{}supportedOS", prefix));
+            element.set_attribute("Id", id.as_ref());
+            application.prepend_child(element, 3);
+        }
+    }
+
+    fn is_common_controls(element: &XmlElement) -> bool {
+        element.local_name() == "dependency"
+            && element
+                .child("dependentAssembly")
+                .and_then(|dependent| dependent.child("assemblyIdentity"))
+                .and_then(|identity| identity.attribute("name"))
+                .is_some_and(|name| name.eq_ignore_ascii_case(COMMON_CONTROLS_NAME))
+    }
+
+    /// Returns if the application depends on version 6 of the common controls.
+    pub fn common_controls(&self) -> bool {
+        self.assembly().elements().any(Self::is_common_controls)
+    }
+
+    /// Set if the application depends on version 6 of the common controls, which enables visual styles.
+    pub fn set_common_controls(&mut self, common_controls: bool) {
+        if !common_controls {
+            self.assembly_mut().remove_children(Self::is_common_controls);
+            return;
+        }
+        if self.common_controls() {
+            return;
+        }
+        let identity = AssemblyIdentity {
+            type_:                  Some("win32".to_string()),
+            name:                   Some(COMMON_CONTROLS_NAME.to_string()),
+            version:                Some("6.0.0.0".to_string()),
+            processor_architecture: Some("*".to_string()),
+            public_key_token:       Some("6595b64144ccf1df".to_string()),
+            language:               Some("*".to_string()),
+        };
+        // the new elements are in the namespace of the assembly element
+        let assembly = self.assembly_mut();
+        let mut element = assembly.new_child("assemblyIdentity");
+        identity.write_element(&mut element);
+        let mut dependent = assembly.new_child("dependentAssembly");
+        dependent.append_child(element, 3);
+        let mut dependency = assembly.new_child("dependency");
+        dependency.append_child(dependent, 2);
+        assembly.append_child(dependency, 1);
+    }
+
+    /// Returns the assembly identity of the application.
+    pub fn assembly_identity(&self) -> Option<AssemblyIdentity> {
+        self.assembly().child("assemblyIdentity").map(AssemblyIdentity::from_element)
+    }
+
+    /// Set the assembly identity of the application.
+    /// Attributes not contained in [`AssemblyIdentity`] are kept.
+    /// Setting `None` removes the assembly identity.
+    pub fn set_assembly_identity(&mut self, identity: Option<&AssemblyIdentity>) {
+        let identity = match identity {
+            Some(identity) => identity,
+            None => {
+                self.remove_element(&["assemblyIdentity"]);
+                return;
+            }
+        };
+        let assembly = self.assembly_mut();
+        if assembly.child("assemblyIdentity").is_none() {
+            assembly.prepend_child(assembly.new_child("assemblyIdentity"), 1);
+        }
+        identity.write_element(assembly.child_mut("assemblyIdentity").unwrap());
+    }
+}
+impl FromStr for Manifest {
+    type Err = ReadError;
+
+    fn from_str(manifest: &str) -> Result<Self, Self::Err> { Self::parse(manifest) }
+}
+impl fmt::Display for Manifest {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result { f.write_str(&self.build()) }
+}
+
+impl ResourceDirectory {
+    /// Returns the names and languages of all manifests.
+    /// Executables use [`CREATEPROCESS_MANIFEST_RESOURCE_ID`] and DLLs use [`ISOLATIONAWARE_MANIFEST_RESOURCE_ID`].
+    ///
+    /// # Returns
+    /// Returns an error if the resource table structure is not well-formed.
+    pub fn manifests(&self) -> Result<Vec<(ResourceEntryName, LANGID)>, ResourceError> {
+        self.data_entries(&ResourceEntryName::ID(RT_MANIFEST as u32), "manifest")
+    }
+
+    /// Get the manifest with the given name and language.
+    ///
+    /// # Returns
+    /// Returns `None` if the manifest does not exist.
+    /// Returns an error if the resource table structure is not well-formed or the manifest is not valid in its encoding.
+    pub fn get_manifest_resource<N: Borrow<ResourceEntryName>>(
+        &self, name: N, language: LANGID,
+    ) -> Result<Option<String>, ResourceError> {
+        Ok(self
+            .get_manifest_resource_with_encoding(name, language)?
+            .map(|(manifest, _)| manifest))
+    }
+
+    /// Get the manifest with the given name and language along with its detected encoding.
+    ///
+    /// # Returns
+    /// Returns `None` if the manifest does not exist.
+    /// Returns an error if the resource table structure is not well-formed or the manifest is not valid in its encoding.
+    pub fn get_manifest_resource_with_encoding<N: Borrow<ResourceEntryName>>(
+        &self, name: N, language: LANGID,
+    ) -> Result<Option<(String, ManifestEncoding)>, ResourceError> {
+        let data = self.data_entry(
+            &ResourceEntryName::ID(RT_MANIFEST as u32),
+            name.borrow(),
+            language,
+            "manifest",
+        )?;
+        match data {
+            Some(data) => {
+                let encoding = ManifestEncoding::detect(data.data())?;
+                Ok(Some((encoding.decode(data.data())?, encoding)))
+            }
+            None => Ok(None),
+        }
+    }
+
+    /// Set the manifest with the given name and language.
+    /// The manifest is written as UTF-8 without a byte order mark.
+    ///
+    /// This will overwrite the existing manifest with the given name and language and keep all other manifests intact.
+    ///
+    /// # Returns
+    /// Returns an error if the resource table structure is not well-formed.
+    pub fn set_manifest_resource<N: Borrow<ResourceEntryName>>(
+        &mut self, name: N, language: LANGID, manifest: &str,
+    ) -> Result<(), ResourceError> {
+        self.set_manifest_resource_with_encoding(name, language, manifest, ManifestEncoding::Utf8)
+    }
+
+    /// Set the manifest with the given name and language using the given encoding.
+    ///
+    /// This will overwrite the existing manifest with the given name and language and keep all other manifests intact.
+    ///
+    /// # Returns
+    /// Returns an error if the resource table structure is not well-formed.
+    pub fn set_manifest_resource_with_encoding<N: Borrow<ResourceEntryName>>(
+        &mut self, name: N, language: LANGID, manifest: &str, encoding: ManifestEncoding,
+    ) -> Result<(), ResourceError> {
+        self.set_data_entry(
+            &ResourceEntryName::ID(RT_MANIFEST as u32),
+            name.borrow(),
+            language,
+            encoding.encode(manifest),
+            "manifest",
+        )
+    }
+
+    /// Remove the manifest with the given name and language.
+    ///
+    /// # Returns
+    /// Returns an error if the resource table structure is not well-formed.
+    pub fn remove_manifest_resource<N: Borrow<ResourceEntryName>>(
+        &mut self, name: N, language: LANGID,
+    ) -> Result<(), ResourceError> {
+        self.remove_data_entry(
+            &ResourceEntryName::ID(RT_MANIFEST as u32),
+            name.borrow(),
+            language,
+            "manifest",
+        )?;
+        Ok(())
+    }
+}
@@ -0,0 +1,311 @@
+//! Menu resources.
+//!
+//! Menus are stored in `RT_MENU` resources either as a normal menu template or as an extended `MENUEX` template.
+//! See <https://learn.microsoft.com/en-us/windows/win32/menurc/menu-resources> for more information.
+
+use alloc::{format, string::String, vec::Vec};
+use core::{borrow::Borrow, iter, mem::size_of};
+
+use zerocopy::IntoBytes;
+
+use crate::{constants::*, errors::*, resource::*, types::*, util::*};
+
+/// Menu item.
+/// This is an entry in a menu template and can contain a submenu.
+///
+/// For normal menus, `flags` contains the `MF_*` option flags without `MF_POPUP` and `MF_END`, which are derived from the structure when building.
+/// For extended menus, `flags` contains the `MFT_*` type flags and `state` contains the `MFS_*` state flags.
+/// The `state` and `help_id` fields are only stored in extended menus, and the `id` is truncated to 16 bits in normal menus.
+#[derive(Debug, Clone, Eq, PartialEq, Default)]
+pub struct MenuItem {
+    pub flags:   u32,
+    pub state:   u32,
+    pub id:      u32,
+    pub help_id: u32,
+    pub text:    String,
+    pub submenu: Option<Vec<MenuItem>>,
+}
+impl MenuItem {
+    /// Returns if the item opens a submenu.
+    pub fn is_popup(&self) -> bool { self.submenu.is_some() }
+
+    /// Returns if the item is a separator.
+    pub fn is_separator(&self) -> bool {
+        self.flags & MFT_SEPARATOR != 0
+            || (self.flags == 0 && self.id == 0 && self.text.is_empty() && self.submenu.is_none())
+    }
+}
+
+/// Menu resource.
+/// This is the content of an `RT_MENU` resource in either the normal or the extended format.
+#[derive(Debug, Clone, Eq, PartialEq, Default)]
+pub struct Menu {
+    pub extended: bool,
+    pub help_id:  u32,
+    pub items:    Vec<MenuItem>,
+}
+impl Menu {
+    /// Parse a menu from a byte slice.
+    ///
+    /// # Returns
+    /// Returns an error if the menu is not well-formed.
+    pub fn parse(data: &[u8]) -> Result<Self, ReadError> {
+        let header = read::<MenuHeader>(data)?;
+        match header.version {
+            0 => {
+                let mut offset = size_of::<MenuHeader>() + header.header_size as usize;
+                let items = Self::parse_items(data, &mut offset, 0)?;
+                Ok(Self {
+                    extended: false,
+                    help_id: 0,
+                    items,
+                })
+            }
+            1 => {
+                let help_id = read::<u32>(data.get(size_of::<MenuHeader>()..).unwrap_or_default())?;
+                let mut offset = size_of::<MenuHeader>() + header.header_size as usize;
+                let items = Self::parse_items_extended(data, &mut offset, 0)?;
+                Ok(Self {
+                    extended: true,
+                    help_id,
+                    items,
+                })
+            }
+            version => Err(ReadError(format!("invalid menu version: {:#x?}", version))),
+        }
+    }
+
+    fn parse_items(
+        data: &[u8], offset: &mut usize, level: usize,
+    ) -> Result<Vec<MenuItem>, ReadError> {
+        let mut items = Vec::new();
+        loop {
+            let option = read::<u16>(data.get(*offset..).unwrap_or_default())
+                .map_err(|_| ReadError(format!("menu item at {:#x?} is truncated", offset)))?;
+            *offset += 2;
+            let mut item = MenuItem {
+                flags: (option & !(MF_POPUP | MF_END)) as u32,
+                ..Default::default()
+            };
+            if option & MF_POPUP == 0 {
+                item.id = read::<u16>(data.get(*offset..).unwrap_or_default())? as u32;
+                *offset += 2;
+            }
+            let (text, length) =
+                read_u16_string_terminated(data.get(*offset..).unwrap_or_default())?;
+            item.text = text;
+            *offset += length;
+            if option & MF_POPUP != 0 {
+                if level >= 32 {
+                    return Err(ReadError("menu is nested too deeply".into()));
+                }
+                item.submenu = Some(Self::parse_items(data, offset, level + 1)?);
+            }
+            items.push(item);
+            if option & MF_END != 0 {
+                break;
+            }
+        }
+        Ok(items)
+    }
+
+    fn parse_items_extended(
+        data: &[u8], offset: &mut usize, level: usize,
+    ) -> Result<Vec<MenuItem>, ReadError> {
+        let mut items = Vec::new();
+        loop {
+            *offset = aligned_to(*offset, 4);
+            let header = read::<MenuExItemHeader>(data.get(*offset..).unwrap_or_default())
+                .map_err(|_| ReadError(format!("menu item at {:#x?} is truncated", offset)))?;
+            *offset += size_of::<MenuExItemHeader>();
+            let (text, length) =
+                read_u16_string_terminated(data.get(*offset..).unwrap_or_default())?;
+            *offset = aligned_to(*offset + length, 4);
+            let mut item = MenuItem {
+                flags: header.type_,
+                state: header.state,
+                id: header.id,
+                text,
+                ..Default::default()
+            };
+            if header.flags & 0x01 != 0 {
+                if level >= 32 {
+                    return Err(ReadError("menu is nested too deeply".into()));
+                }
+                item.help_id = read::<u32>(data.get(*offset..).unwrap_or_default())?;
+                *offset += 4;
+                item.submenu = Some(Self::parse_items_extended(data, offset, level + 1)?);
+            }
+            items.push(item);
+            if header.flags & MF_END != 0 {
+                break;
+            }
+        }
+        Ok(items)
+    }
+
+    /// Build the menu into raw bytes to be included in a resource table.
+    /// Empty menus and submenus are written with a single separator, as the format cannot represent empty item lists.
+    pub fn build(&self) -> Vec<u8> {
+        let mut data = Vec::new();
+        if self.extended {
+            data.extend(
+                MenuHeader {
+                    version:     1,
+                    header_size: 4,
+                }
+                .as_bytes(),
+            );
+            data.extend(self.help_id.to_le_bytes());
+            Self::build_items_extended(&self.items, &mut data);
+        } else {
+            data.extend(MenuHeader::default().as_bytes());
+            Self::build_items(&self.items, &mut data);
+        }
+        data
+    }
+
+    fn build_items(items: &[MenuItem], data: &mut Vec<u8>) {
+        // the end of a list is marked on its last item, so empty lists are written as a single separator
+        let separator = [MenuItem::default()];
+        let items = if items.is_empty() {
+            &separator[..]
+        } else {
+            items
+        };
+        for (i, item) in items.iter().enumerate() {
+            let mut option = item.flags as u16 & !(MF_POPUP | MF_END);
+            if item.submenu.is_some() {
+                option |= MF_POPUP;
+            }
+            if i == items.len() - 1 {
+                option |= MF_END;
+            }
+            data.extend(option.to_le_bytes());
+            if item.submenu.is_none() {
+                data.extend((item.id as u16).to_le_bytes());
+            }
+            data.extend(string_to_u16(&item.text));
+            if let Some(submenu) = &item.submenu {
+                Self::build_items(submenu, data);
+            }
+        }
+    }
+
+    fn build_items_extended(items: &[MenuItem], data: &mut Vec<u8>) {
+        let separator = [MenuItem {
+            flags: MFT_SEPARATOR,
+            ..Default::default()
+        }];
+        let items = if items.is_empty() {
+            &separator[..]
+        } else {
+            items
+        };
+        for (i, item) in items.iter().enumerate() {
+            let mut flags = 0u16;
+            if item.submenu.is_some() {
+                flags |= 0x01;
+            }
+            if i == items.len() - 1 {
+                flags |= MF_END;
+            }
+            data.extend(iter::repeat_n(0, aligned_to(data.len(), 4) - data.len()));
+            data.extend(
+                MenuExItemHeader {
+                    type_: item.flags,
+                    state: item.state,
+                    id: item.id,
+                    flags,
+                }
+                .as_bytes(),
+            );
+            data.extend(string_to_u16(&item.text));
+            if let Some(submenu) = &item.submenu {
+                data.extend(iter::repeat_n(0, aligned_to(data.len(), 4) - data.len()));
+                data.extend(item.help_id.to_le_bytes());
+                Self::build_items_extended(submenu, data);
+            }
+        }
+    }
+
+    /// Returns the item with the given ID, searching all submenus.
+    pub fn item(&self, id: u32) -> Option<&MenuItem> { Self::find_item(&self.items, id) }
+
+    /// Returns the mutable item with the given ID, searching all submenus.
+    pub fn item_mut(&mut self, id: u32) -> Option<&mut MenuItem> {
+        Self::find_item_mut(&mut self.items, id)
+    }
+
+    fn find_item(items: &[MenuItem], id: u32) -> Option<&MenuItem> {
+        items.iter().find_map(|item| match &item.submenu {
+            Some(submenu) => Self::find_item(submenu, id),
+            None if item.id == id && !item.is_separator() => Some(item),
+            None => None,
+        })
+    }
+
+    fn find_item_mut(items: &mut [MenuItem], id: u32) -> Option<&mut MenuItem> {
+        items.iter_mut().find_map(|item| match item.submenu {
+            Some(ref mut submenu) => Self::find_item_mut(submenu, id),
+            None if item.id == id && !item.is_separator() => Some(item),
+            None => None,
+        })
+    }
+}
+
+impl ResourceDirectory {
+    /// Get a menu.
+    ///
+    /// # Returns
+    /// Returns `None` if the menu does not exist.
+    /// Returns an error if the menu resource directory or the menu is invalid.
+    pub fn get_menu<N: Borrow<ResourceEntryName>>(
+        &self, name: N, language: LANGID,
+    ) -> Result<Option<Menu>, ResourceError> {
+        let data = self.data_entry(
+            &ResourceEntryName::ID(RT_MENU as u32),
+            name.borrow(),
+            language,
+            "menu",
+        )?;
+        match data {
+            Some(data) => Ok(Some(Menu::parse(data.data())?)),
+            None => Ok(None),
+        }
+    }
+
+    /// Set a menu.
+    ///
+    /// This will overwrite the existing menu with the given name and language.
+    ///
+    /// # Returns
+    /// Returns an error if the resource table structure is not well-formed.
+    pub fn set_menu<N: Borrow<ResourceEntryName>>(
+        &mut self, name: N, language: LANGID, menu: &Menu,
+    ) -> Result<(), ResourceError> {
+        self.set_data_entry(
+            &ResourceEntryName::ID(RT_MENU as u32),
+            name.borrow(),
+            language,
+            menu.build(),
+            "menu",
+        )
+    }
+
+    /// Remove a menu.
+    ///
+    /// # Returns
+    /// Returns an error if the resource table structure is not well-formed.
+    pub fn remove_menu<N: Borrow<ResourceEntryName>>(
+        &mut self, name: N, language: LANGID,
+    ) -> Result<(), ResourceError> {
+        self.remove_data_entry(
+            &ResourceEntryName::ID(RT_MENU as u32),
+            name.borrow(),
+            language,
+            "menu",
+        )?;
+        Ok(())
+    }
+}
@@ -0,0 +1,425 @@
+//! Message table resources.
+//!
+//! Message tables are stored in `RT_MESSAGETABLE` resources in the `MESSAGE_RESOURCE_DATA` layout,
+//! which consists of blocks of consecutive message IDs each pointing to a list of ANSI or Unicode entries.
+//! See <https://learn.microsoft.com/en-us/windows/win32/api/winnt/ns-winnt-message_resource_data> for more information.
+
+use alloc::{
+    collections::BTreeMap,
+    format,
+    string::{String, ToString},
+    vec::Vec,
+};
+use core::{borrow::Borrow, iter, mem::size_of};
+
+use foldhash::fast::RandomState;
+use indexmap::IndexMap;
+use zerocopy::IntoBytes;
+
+use crate::{constants::*, errors::*, resource::*, types::*, util::*};
+
+/// Message table entry.
+/// The text is stored as UTF-16 when `unicode` is set, and as single-byte ANSI text otherwise.
+///
+/// ANSI text is read as Latin-1, and characters that cannot be represented are written as `?`.
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct MessageTableEntry {
+    pub text:    String,
+    pub unicode: bool,
+}
+impl Default for MessageTableEntry {
+    fn default() -> Self {
+        Self {
+            text:    String::new(),
+            unicode: true,
+        }
+    }
+}
+
+/// Message table resource.
+/// This is the content of an `RT_MESSAGETABLE` resource.
+///
+/// Entries are ordered by message ID, and blocks of consecutive IDs are computed when building.
+#[derive(Debug, Clone, Eq, PartialEq, Default)]
+pub struct MessageTable {
+    pub entries: BTreeMap<u32, MessageTableEntry>,
+}
+impl MessageTable {
+    /// Parse a message table from a byte slice.
+    ///
+    /// # Returns
+    /// Returns an error if the message table is not well-formed.
+    pub fn parse(data: &[u8]) -> Result<Self, ReadError> {
+        let mut entries = BTreeMap::new();
+        let number_of_blocks = read::<u32>(data)? as usize;
+        for i in 0..number_of_blocks {
+            let block = read::<MessageResourceBlock>(
+                data.get(4 + i * size_of::<MessageResourceBlock>()..).unwrap_or_default(),
+            )
+            .map_err(|_| ReadError(format!("message table block {} is truncated", i)))?;
+            if block.low_id > block.high_id {
+                return Err(ReadError(format!(
+                    "message table block {} has invalid range {:#x?}..{:#x?}",
+                    i,
+                    { block.low_id },
+                    { block.high_id }
+                )));
+            }
+            let mut offset = block.offset_to_entries as usize;
+            for id in block.low_id..=block.high_id {
+                let header =
+                    read::<MessageResourceEntryHeader>(data.get(offset..).unwrap_or_default())
+                        .map_err(|_| ReadError(format!("message {:#x?} is truncated", id)))?;
+                let length = header.length as usize;
+                if length < size_of::<MessageResourceEntryHeader>() || offset + length > data.len()
+                {
+                    return Err(ReadError(format!(
+                        "message {:#x?} has invalid length {:#x?}",
+                        id, length
+                    )));
+                }
+                let text = &data[offset + size_of::<MessageResourceEntryHeader>()..offset + length];
+                let unicode = header.flags & 0x0001 != 0;
+                let text = if unicode {
+                    read_u16_string(text)?
+                } else {
+                    text.iter().take_while(|&&c| c != 0).map(|&c| c as char).collect()
+                };
+                entries.insert(id, MessageTableEntry { text, unicode });
+                offset += length;
+            }
+        }
+        Ok(Self { entries })
+    }
+
+    /// Build the message table into raw bytes to be included in a resource table.
+    pub fn build(&self) -> Vec<u8> {
+        let blocks = self.blocks();
+
+        let mut data = Vec::new();
+        data.extend((blocks.len() as u32).to_le_bytes());
+        let mut entries = Vec::new();
+        let entries_offset = 4 + blocks.len() * size_of::<MessageResourceBlock>();
+        for (low_id, high_id) in blocks {
+            data.extend(
+                MessageResourceBlock {
+                    low_id,
+                    high_id,
+                    offset_to_entries: (entries_offset + entries.len()) as u32,
+                }
+                .as_bytes(),
+            );
+            for (_, entry) in self.entries.range(low_id..=high_id) {
+                let mut text = if entry.unicode {
+                    string_to_u16(&entry.text)
+                } else {
+                    let mut text = entry
+                        .text
+                        .chars()
+                        .map(|c| if (c as u32) < 0x100 { c as u8 } else { b'?' })
+                        .collect::<Vec<_>>();
+                    text.push(0);
+                    text
+                };
+                text.extend(iter::repeat_n(
+                    0,
+                    aligned_to(text.len() + size_of::<MessageResourceEntryHeader>(), 4)
+                        - size_of::<MessageResourceEntryHeader>()
+                        - text.len(),
+                ));
+                entries.extend(
+                    MessageResourceEntryHeader {
+                        length: (text.len() + size_of::<MessageResourceEntryHeader>()) as u16,
+                        flags:  entry.unicode as u16,
+                    }
+                    .as_bytes(),
+                );
+                entries.extend(text);
+            }
+        }
+        data.extend(entries);
+        data
+    }
+
+    /// Returns the ranges of consecutive message IDs as inclusive `(low, high)` pairs.
+    pub fn blocks(&self) -> Vec<(u32, u32)> {
+        let mut blocks: Vec<(u32, u32)> = Vec::new();
+        for &id in self.entries.keys() {
+            match blocks.last_mut() {
+                Some((_, high_id)) if high_id.checked_add(1) == Some(id) => *high_id = id,
+                _ => blocks.push((id, id)),
+            }
+        }
+        blocks
+    }
+
+    /// Returns the text of the message with the given ID.
+    pub fn get(&self, id: u32) -> Option<&str> {
+        self.entries.get(&id).map(|entry| entry.text.as_str())
+    }
+
+    /// Insert a Unicode message with the given ID.
+    /// If a message with the given ID already exists, it will be replaced.
+    /// # Returns
+    /// The replaced entry.
+    pub fn insert<S: Into<String>>(&mut self, id: u32, text: S) -> Option<MessageTableEntry> {
+        self.entries.insert(id, MessageTableEntry {
+            text:    text.into(),
+            unicode: true,
+        })
+    }
+
+    /// Remove the message with the given ID.
+    /// # Returns
+    /// The removed entry.
+    pub fn remove(&mut self, id: u32) -> Option<MessageTableEntry> { self.entries.remove(&id) }
+
+    /// Compile message text source in the format of the Windows message compiler (`.mc` files).
+    ///
+    /// The supported subset includes the `SeverityNames`, `FacilityNames` and `LanguageNames` header statements
+    /// and messages with `MessageId`, `Severity`, `Facility`, `SymbolicName` and `Language` statements.
+    /// `MessageIdTypedef` and `OutputBase` are accepted and ignored, as are comment lines starting with `;`.
+    /// A `MessageId` without a value or with a `+` increment continues from the last message ID of the same facility.
+    /// Message text lines are terminated with `\r\n` and insert sequences like `%1` are kept as written.
+    ///
+    /// Messages are compiled into Unicode entries, with a separate message table for each language.
+    ///
+    /// See <https://learn.microsoft.com/en-us/windows/win32/eventlog/message-text-files> for more information.
+    ///
+    /// # Returns
+    /// Returns the message tables by language, or an error if the source is invalid.
+    pub fn compile(source: &str) -> Result<IndexMap<LANGID, MessageTable, RandomState>, ReadError> {
+        let mut severities = IndexMap::<String, u32, RandomState>::default();
+        for (name, value) in [
+            ("success", 0x0),
+            ("informational", 0x1),
+            ("warning", 0x2),
+            ("error", 0x3),
+        ] {
+            severities.insert(name.to_string(), value);
+        }
+        let mut facilities = IndexMap::<String, u32, RandomState>::default();
+        for (name, value) in [("system", 0x0ff), ("application", 0xfff)] {
+            facilities.insert(name.to_string(), value);
+        }
+        let mut languages = IndexMap::<String, LANGID, RandomState>::default();
+        languages.insert("english".to_string(), LANGUAGE_ID_EN_US);
+
+        let mut tables = IndexMap::<LANGID, MessageTable, RandomState>::default();
+        let mut severity = 0;
+        let mut facility = 0;
+        // message IDs without a value continue from the last ID of the facility of the message,
+        // which is only known once the message text starts
+        let mut message_id: Option<MessageIdValue> = None;
+        let mut resolved_id: Option<u32> = None;
+        let mut last_message_ids = IndexMap::<u32, u32, RandomState>::default();
+
+        let mut lines = source.lines().enumerate();
+        while let Some((line_number, line)) = lines.next() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with(';') {
+                continue;
+            }
+            let (key, value) = line.split_once('=').ok_or_else(|| {
+                ReadError(format!("line {}: expected statement: {:?}", line_number + 1, line))
+            })?;
+            let key = key.trim().to_ascii_lowercase();
+            let mut value = value.trim().to_string();
+            if value.starts_with('(') {
+                while !value.contains(')') {
+                    let (_, line) = lines.next().ok_or_else(|| {
+                        ReadError(format!("line {}: unterminated list", line_number + 1))
+                    })?;
+                    value.push(' ');
+                    value.push_str(line.trim());
+                }
+            }
+            let error = |message: &str| ReadError(format!("line {}: {}", line_number + 1, message));
+
+            match key.as_str() {
+                "messageidtypedef" | "outputbase" => {}
+                "severitynames" | "facilitynames" | "languagenames" => {
+                    let list = value
+                        .strip_prefix('(')
+                        .and_then(|value| value.split_once(')'))
+                        .map(|(list, _)| list)
+                        .ok_or_else(|| error("expected list"))?;
+                    // join whitespace around separators so definitions can be split on whitespace
+                    let mut definitions = String::new();
+                    for part in list.split_whitespace() {
+                        if !definitions.is_empty()
+                            && !definitions.ends_with(['=', ':'])
+                            && !part.starts_with(['=', ':'])
+                        {
+                            definitions.push(' ');
+                        }
+                        definitions.push_str(part);
+                    }
+                    for definition in definitions.split_whitespace() {
+                        let (name, value) = definition
+                            .split_once('=')
+                            .ok_or_else(|| error("expected name=value"))?;
+                        let value = value.split(':').next().unwrap_or_default();
+                        let value = parse_number(value).ok_or_else(|| error("invalid number"))?;
+                        let name = name.to_ascii_lowercase();
+                        match key.as_str() {
+                            "severitynames" => {
+                                severities.insert(name, value);
+                            }
+                            "facilitynames" => {
+                                facilities.insert(name, value);
+                            }
+                            _ => {
+                                languages.insert(name, value as LANGID);
+                            }
+                        }
+                    }
+                }
+                "messageid" => {
+                    message_id = Some(if value.is_empty() {
+                        MessageIdValue::Increment(1)
+                    } else if let Some(increment) = value.strip_prefix('+') {
+                        MessageIdValue::Increment(
+                            parse_number(increment.trim())
+                                .ok_or_else(|| error("invalid number"))?,
+                        )
+                    } else {
+                        MessageIdValue::Absolute(
+                            parse_number(&value).ok_or_else(|| error("invalid number"))?,
+                        )
+                    });
+                    resolved_id = None;
+                }
+                "severity" => {
+                    message_id.ok_or_else(|| error("severity outside of message"))?;
+                    severity = *severities
+                        .get(&value.to_ascii_lowercase())
+                        .ok_or_else(|| error("unknown severity"))?;
+                }
+                "facility" => {
+                    message_id.ok_or_else(|| error("facility outside of message"))?;
+                    facility = *facilities
+                        .get(&value.to_ascii_lowercase())
+                        .ok_or_else(|| error("unknown facility"))?;
+                }
+                "symbolicname" => {
+                    message_id.ok_or_else(|| error("symbolic name outside of message"))?;
+                }
+                "language" => {
+                    let message_id =
+                        message_id.ok_or_else(|| error("language outside of message"))?;
+                    let id = match resolved_id {
+                        Some(id) => id,
+                        None => {
+                            let last = last_message_ids.get(&facility).copied().unwrap_or(0);
+                            let id = match message_id {
+                                MessageIdValue::Increment(increment) => {
+                                    last.wrapping_add(increment)
+                                }
+                                MessageIdValue::Absolute(id) => id,
+                            };
+                            if id > 0xffff {
+                                return Err(error("message id is larger than 0xffff"));
+                            }
+                            last_message_ids.insert(facility, id);
+                            resolved_id = Some(id);
+                            id
+                        }
+                    };
+                    let language = *languages
+                        .get(&value.to_ascii_lowercase())
+                        .ok_or_else(|| error("unknown language"))?;
+                    let mut text = String::new();
+                    loop {
+                        let (_, line) =
+                            lines.next().ok_or_else(|| error("unterminated message"))?;
+                        if line.trim_end() == "." {
+                            break;
+                        }
+                        text.push_str(line);
+                        text.push_str("\r\n");
+                    }
+                    let id = (severity & 0x3) << 30 | (facility & 0xfff) << 16 | id;
+                    tables.entry(language).or_default().insert(id, text);
+                }
+                _ => return Err(error(&format!("unknown statement: {:?}", key))),
+            }
+        }
+
+        Ok(tables)
+    }
+}
+
+/// Value of a `MessageId` statement.
+#[derive(Debug, Clone, Copy)]
+enum MessageIdValue {
+    /// Increment of the last message ID of the facility, which is `1` if no value is given.
+    Increment(u32),
+    /// Absolute message ID.
+    Absolute(u32),
+}
+
+fn parse_number(value: &str) -> Option<u32> {
+    let value = value.trim();
+    match value.strip_prefix("0x").or_else(|| value.strip_prefix("0X")) {
+        Some(hex) => u32::from_str_radix(hex, 16).ok(),
+        None => value.parse().ok(),
+    }
+}
+
+impl ResourceDirectory {
+    /// Get a message table.
+    /// Message tables are usually stored with the name ID `1`.
+    ///
+    /// # Returns
+    /// Returns `None` if the message table does not exist.
+    /// Returns an error if the message table resource directory or the message table is invalid.
+    pub fn get_message_table<N: Borrow<ResourceEntryName>>(
+        &self, name: N, language: LANGID,
+    ) -> Result<Option<MessageTable>, ResourceError> {
+        let data = self.data_entry(
+            &ResourceEntryName::ID(RT_MESSAGETABLE as u32),
+            name.borrow(),
+            language,
+            "message",
+        )?;
+        match data {
+            Some(data) => Ok(Some(MessageTable::parse(data.data())?)),
+            None => Ok(None),
+        }
+    }
+
+    /// Set a message table.
+    ///
+    /// This will overwrite the existing message table with the given name and language.
+    ///
+    /// # Returns
+    /// Returns an error if the resource table structure is not well-formed.
+    pub fn set_message_table<N: Borrow<ResourceEntryName>>(
+        &mut self, name: N, language: LANGID, table: &MessageTable,
+    ) -> Result<(), ResourceError> {
+        self.set_data_entry(
+            &ResourceEntryName::ID(RT_MESSAGETABLE as u32),
+            name.borrow(),
+            language,
+            table.build(),
+            "message",
+        )
+    }
+
+    /// Remove a message table.
+    ///
+    /// # Returns
+    /// Returns an error if the resource table structure is not well-formed.
+    pub fn remove_message_table<N: Borrow<ResourceEntryName>>(
+        &mut self, name: N, language: LANGID,
+    ) -> Result<(), ResourceError> {
+        self.remove_data_entry(
+            &ResourceEntryName::ID(RT_MESSAGETABLE as u32),
+            name.borrow(),
+            language,
+            "message",
+        )?;
+        Ok(())
+    }
+}
@@ -7,7 +7,14 @@ use alloc::{
     string::{String, ToString},
     vec::Vec,
 };
-use core::{borrow::Borrow, cmp::Ordering, iter, mem::size_of};
+use core::{
+    borrow::Borrow,
+    cmp::Ordering,
+    fmt,
+    hash::{Hash, Hasher},
+    mem::size_of,
+    str::FromStr,
+};
 
 use debug_ignore::DebugIgnore;
 use foldhash::fast::RandomState;
@@ -16,7 +23,7 @@ use log::{error, trace, warn};
 use zerocopy::IntoBytes;
 
 #[cfg(feature = "images")]
-pub use image::DynamicImage;
+pub use image::{DynamicImage, imageops::FilterType};
 
 /// Trait for data types that can be converted to icon data.
 ///
@@ -62,24 +69,7 @@ impl ToIcon for Vec<u8> {
 #[cfg(feature = "images")]
 impl ToIcon for &DynamicImage {
     fn icons(&self) -> Result<Vec<Vec<u8>>, ResourceError> {
-        use image::{ImageFormat, imageops::FilterType::Lanczos3};
-        use std::io::Cursor;
-        const RESOLUTIONS: &[u32] = &[256, 128, 48, 32, 24, 16];
-        RESOLUTIONS
-            .iter()
-            .map(|&size| {
-                let mut data = Vec::new();
-                self.resize_exact(size, size, Lanczos3)
-                    .to_rgba8()
-                    .write_to(&mut Cursor::new(&mut data), ImageFormat::Ico)?;
-                // prepend 12 bytes of ICO directory entry metadata + 2-byte dummy id
-                let mut result = Vec::with_capacity(14 + data.len() - 22);
-                result.extend_from_slice(&data[6..18]);
-                result.extend_from_slice(&[0u8; 2]);
-                result.extend_from_slice(&data[22..]);
-                Ok(result)
-            })
-            .collect::<Result<Vec<Vec<u8>>, ResourceError>>()
+        crate::IconOptions::default().icons(self)
     }
 }
 #[cfg(feature = "images")]
@@ -87,7 +77,31 @@ impl ToIcon for DynamicImage {
     fn icons(&self) -> Result<Vec<Vec<u8>>, ResourceError> { (&self).icons() }
 }
 
-use crate::{constants::*, errors::*, types::*, util::*};
+use crate::{constants::*, errors::*, types::*, util::*, version::*};
+
+/// Build an ICO file from icon data in the representation returned by [`ToIcon::icons`].
+pub(crate) fn build_icon_file(icons: &[Vec<u8>]) -> Vec<u8> {
+    let mut data = Vec::new();
+    data.extend(
+        IconDirectory {
+            reserved: 0,
+            type_:    1,
+            count:    icons.len() as u16,
+        }
+        .as_bytes(),
+    );
+    let mut offset = 6 + icons.len() * 16;
+    for icon in icons {
+        data.extend(&icon[..8]);
+        data.extend(((icon.len() - 14) as u32).to_le_bytes());
+        data.extend((offset as u32).to_le_bytes());
+        offset += icon.len() - 14;
+    }
+    for icon in icons {
+        data.extend(&icon[14..]);
+    }
+    data
+}
 
 /// Portable executable resource directory.
 ///
@@ -225,6 +239,7 @@ impl ResourceDirectory {
     /// This will overwrite the group icon directory with the `MAINICON` name if it exists and keep all other group icon directories intact.
     /// This will not remove any existing icons.
     /// To remove the existing main icon directory and the icons referenced by, call [`remove_main_icon`](ResourceDirectory::remove_main_icon) before setting a new one.
+    /// Icons left unreferenced can be removed with [`remove_unreferenced_images`](ResourceDirectory::remove_unreferenced_images).
     ///
     /// # Returns
     /// Returns an error if the new icon not a valid image or the resource table structure is not well-formed.
@@ -243,32 +258,23 @@ impl ResourceDirectory {
             }
         };
 
-        // find the first free icon id
-        let first_free_icon_id = icon_table
-            .entries
-            .keys()
-            .filter_map(|k| match k {
-                ResourceEntryName::ID(id) => Some(*id),
-                _ => None,
-            })
-            .max()
-            .unwrap_or(0)
-            + 1;
-
         // read the icon and resize it to the different resolutions
         let icons = icon.icons()?;
 
+        // find the first free icon id
+        let first_free_icon_id = icon_table.next_free_ids(icons.len(), "icon table")?;
+
         // add the icons to the icon table
         let mut icon_directory_entries = Vec::new();
         for (i, icon) in icons.iter().enumerate() {
-            let id = first_free_icon_id + i as u32;
+            let id = first_free_icon_id + i as u16;
             let mut inner_table = ResourceTable::default();
             inner_table.insert(
                 ResourceEntryName::ID(LANGUAGE_ID_EN_US as u32),
                 ResourceEntry::Data(ResourceData {
                     data:     {
                         let mut entry = read::<IconDirectoryEntry>(&icon[..14])?;
-                        entry.id = id as u16;
+                        entry.id = id;
                         entry.bytes = (icon.len() - 14) as u32;
                         icon_directory_entries.push(entry);
                         icon[14..].to_vec().into()
@@ -277,7 +283,7 @@ impl ResourceDirectory {
                     reserved: 0,
                 }),
             );
-            icon_table.insert(ResourceEntryName::ID(id), ResourceEntry::Table(inner_table));
+            icon_table.insert(ResourceEntryName::ID(id as u32), ResourceEntry::Table(inner_table));
         }
 
         // find the group icon table
@@ -332,23 +338,21 @@ impl ResourceDirectory {
     /// Set the main icon of the executable from a file.
     /// The file must contain a valid image.
     /// The image is resized to the different icon resolutions when the `images` feature is enabled.
+    /// The images of an `.ico` file are kept and only the missing resolutions are added, see [`IconOptions::complete`](crate::IconOptions::complete).
     ///
     /// See [`set_main_icon`](ResourceDirectory::set_main_icon) for more information.
     ///
     /// # Returns
     /// Returns an error if the file is not a valid image or the resource table structure is not well-formed.
     pub fn set_main_icon_file(&mut self, path: &str) -> Result<(), ResourceError> {
-        #[cfg(feature = "images")]
-        let icon = image::ImageReader::open(path)?.decode()?;
-        #[cfg(not(feature = "images"))]
-        let icon = std::fs::read(path)?;
-        self.set_main_icon(icon)
+        self.set_main_icon_reader(&mut std::fs::File::open(path)?)
     }
 
     #[cfg(feature = "std")]
     /// Set the main icon of the executable from a reader.
     /// The reader must contain a valid image.
     /// The image is resized to the different icon resolutions when the `images` feature is enabled.
+    /// The images of an `.ico` file are kept and only the missing resolutions are added, see [`IconOptions::complete`](crate::IconOptions::complete).
     ///
     /// See [`set_main_icon`](ResourceDirectory::set_main_icon) for more information.
     ///
@@ -360,6 +364,10 @@ impl ResourceDirectory {
         let mut icon = Vec::new();
         reader.read_to_end(&mut icon)?;
         #[cfg(feature = "images")]
+        if icon.starts_with(&[0, 0, 1, 0]) {
+            return self.set_main_icon(crate::IconOptions::default().complete(&icon)?);
+        }
+        #[cfg(feature = "images")]
         let icon = image::load_from_memory(&icon)?;
         self.set_main_icon(icon)
     }
@@ -485,62 +493,37 @@ impl ResourceDirectory {
     }
 
     /// Get the version information of the executable.
+    /// This is the first version information in the `en-US` language or its fallback as chosen by [`find_resource`](ResourceDirectory::find_resource).
+    /// Use [`get_version_info_resource`](ResourceDirectory::get_version_info_resource) to get the version information with a specific name and language.
     ///
     /// # Returns
     /// Returns `None` if no version information exists.
     /// Returns an error if the version resource directory is invalid.
     pub fn get_version_info(&self) -> Result<Option<VersionInfo>, ResourceError> {
-        if self.root.entries.is_empty() {
-            return Ok(None);
-        }
-
-        // find the group table
-        let version_table = self.root.get(ResourceEntryName::ID(RT_VERSION as u32));
-        let version_table = match version_table {
-            Some(ResourceEntry::Table(t)) => t,
-            Some(_) => {
-                return Err(ResourceError::InvalidTable(
-                    "version table is not a table".to_string(),
-                ));
-            }
-            _ => return Ok(None),
-        };
-        if version_table.entries.is_empty() {
-            return Ok(None);
-        }
-
         // find the main version directory table
-        let inner_table = version_table.entries.first().map(|(_, v)| v);
-        let inner_table = match inner_table {
-            Some(ResourceEntry::Table(t)) => t,
-            Some(_) => {
-                return Err(ResourceError::InvalidTable(
-                    "inner version table is not a table".to_string(),
-                ));
-            }
+        let version_table =
+            match self.root.table(ResourceEntryName::ID(RT_VERSION as u32), "version table")? {
+                Some(table) => table,
+                None => return Ok(None),
+            };
+        let name = match version_table.entries.keys().next() {
+            Some(name) => name,
             None => return Ok(None),
         };
-        if inner_table.entries.is_empty() {
-            return Ok(None);
-        }
 
         // find the main version directory
-        let version_directory_entry = inner_table
-            .entries
-            .iter()
-            .find(|(name, _)| **name == ResourceEntryName::ID(LANGUAGE_ID_EN_US as u32))
-            .or_else(|| inner_table.entries.first())
-            .map(|(_, v)| v)
-            .unwrap();
-        if version_directory_entry.is_table() {
-            return Err(ResourceError::InvalidTable("version table entry is not data".to_string()));
-        }
-        let version_directory_entry = version_directory_entry.as_data().unwrap();
+        let version_directory_entry =
+            match self.find_resource(ResourceType::Version, name, LANGUAGE_ID_EN_US, None)? {
+                Some((_, data)) => data,
+                None => return Ok(None),
+            };
 
         Ok(Some(VersionInfo::parse(&version_directory_entry.data)?))
     }
 
     /// Set the version information of the executable.
+    /// The version information is written with the ID 1 in the `en-US` language.
+    /// Use [`set_version_info_resource`](ResourceDirectory::set_version_info_resource) to set the version information with a specific name and language.
     ///
     /// This will overwrite the existing version information.
     ///
@@ -642,70 +625,70 @@ impl ResourceDirectory {
     }
 
     /// Get the manifest of the executable.
+    /// This is the first manifest in the `en-US` language or its fallback as chosen by [`find_resource`](ResourceDirectory::find_resource).
+    /// Use [`get_manifest_resource`](ResourceDirectory::get_manifest_resource) to get a manifest with a specific name and language.
     ///
     /// # Returns
     /// Returns `None` if no manifest exists.
-    /// Returns an error if the manifest resource directory is invalid.
+    /// Returns an error if the manifest resource directory is invalid or the manifest is not valid in its encoding.
     pub fn get_manifest(&self) -> Result<Option<String>, ResourceError> {
-        if self.root.entries.is_empty() {
-            return Ok(None);
-        }
-
-        // find the manifest table
-        let manifest_table = self.root.get(ResourceEntryName::ID(RT_MANIFEST as u32));
-        let manifest_table = match manifest_table {
-            Some(ResourceEntry::Table(t)) => t,
-            Some(_) => {
-                return Err(ResourceError::InvalidTable(
-                    "manifest table is not a table".to_string(),
-                ));
-            }
-            _ => return Ok(None),
-        };
-        if manifest_table.entries.is_empty() {
-            return Ok(None);
-        }
+        Ok(self.get_manifest_with_encoding()?.map(|(manifest, _)| manifest))
+    }
 
+    /// Get the manifest of the executable along with its detected encoding.
+    ///
+    /// # Returns
+    /// Returns `None` if no manifest exists.
+    /// Returns an error if the manifest resource directory is invalid or the manifest is not valid in its encoding.
+    pub fn get_manifest_with_encoding(
+        &self,
+    ) -> Result<Option<(String, crate::ManifestEncoding)>, ResourceError> {
         // find the main manifest directory table
-        let inner_table = manifest_table.entries.first().map(|(_, v)| v);
-        let inner_table = match inner_table {
-            Some(ResourceEntry::Table(t)) => t,
-            Some(_) => {
-                return Err(ResourceError::InvalidTable(
-                    "inner manifest table is not a table".to_string(),
-                ));
-            }
+        let manifest_table =
+            match self.root.table(ResourceEntryName::ID(RT_MANIFEST as u32), "manifest table")? {
+                Some(table) => table,
+                None => return Ok(None),
+            };
+        let name = match manifest_table.entries.keys().next() {
+            Some(name) => name,
             None => return Ok(None),
         };
-        if inner_table.entries.is_empty() {
-            return Ok(None);
-        }
 
         // find the main manifest directory
-        let manifest_directory_entry = inner_table
-            .entries
-            .iter()
-            .find(|(name, _)| **name == ResourceEntryName::ID(LANGUAGE_ID_EN_US as u32))
-            .or_else(|| inner_table.entries.first())
-            .map(|(_, v)| v)
-            .unwrap();
-        if manifest_directory_entry.is_table() {
-            return Err(ResourceError::InvalidTable(
-                "manifest table entry is not data".to_string(),
-            ));
-        }
-        let manifest_directory_entry = manifest_directory_entry.as_data().unwrap();
+        let manifest_directory_entry =
+            match self.find_resource(ResourceType::Manifest, name, LANGUAGE_ID_EN_US, None)? {
+                Some((_, data)) => data,
+                None => return Ok(None),
+            };
 
-        Ok(Some(String::from_utf8_lossy(&manifest_directory_entry.data).to_string()))
+        let encoding = crate::ManifestEncoding::detect(&manifest_directory_entry.data)?;
+        Ok(Some((encoding.decode(&manifest_directory_entry.data)?, encoding)))
     }
 
     /// Set the manifest of the executable.
+    /// The manifest is written with the ID 1 in the `en-US` language.
+    /// Use [`set_manifest_resource`](ResourceDirectory::set_manifest_resource) to set a manifest with a specific name and language.
+    ///
+    /// The manifest is written as UTF-8 without a byte order mark.
     ///
     /// This will overwrite the existing manifest.
     ///
     /// # Returns
     /// Returns an error if the resource table structure is not well-formed.
     pub fn set_manifest(&mut self, manifest: &str) -> Result<(), ResourceError> {
+        self.set_manifest_with_encoding(manifest, crate::ManifestEncoding::Utf8)
+    }
+
+    /// Set the manifest of the executable using the given encoding.
+    /// Use the encoding returned by [`get_manifest_with_encoding`](ResourceDirectory::get_manifest_with_encoding) to keep the original encoding.
+    ///
+    /// This will overwrite the existing manifest.
+    ///
+    /// # Returns
+    /// Returns an error if the resource table structure is not well-formed.
+    pub fn set_manifest_with_encoding(
+        &mut self, manifest: &str, encoding: crate::ManifestEncoding,
+    ) -> Result<(), ResourceError> {
         if self.root.get(ResourceEntryName::ID(RT_MANIFEST as u32)).is_none() {
             self.root.insert(
                 ResourceEntryName::ID(RT_MANIFEST as u32),
@@ -737,7 +720,7 @@ impl ResourceDirectory {
         inner_table.insert_at(
             ResourceEntryName::ID(LANGUAGE_ID_EN_US as u32),
             ResourceEntry::Data(ResourceData {
-                data:     manifest.as_bytes().to_vec().into(),
+                data:     encoding.encode(manifest).into(),
                 codepage: CODE_PAGE_ID_EN_US as u32,
                 reserved: 0,
             }),
@@ -799,6 +782,327 @@ impl ResourceDirectory {
         Ok(())
     }
 
+    /// Get the names and languages of all resource data with the given type.
+    /// The `kind` is used to describe the resource in the returned error.
+    pub(crate) fn data_entries(
+        &self, type_: &ResourceEntryName, kind: &str,
+    ) -> Result<Vec<(ResourceEntryName, LANGID)>, ResourceError> {
+        let type_table = match self.root.table(type_, &format!("{} table", kind))? {
+            Some(table) => table,
+            None => return Ok(Vec::new()),
+        };
+        let mut entries = Vec::new();
+        for (name, inner_table) in type_table.entries.iter() {
+            let inner_table = inner_table.as_table().ok_or_else(|| {
+                ResourceError::InvalidTable(format!("inner {} table is not a table", kind))
+            })?;
+            for language in inner_table.entries.keys() {
+                if let ResourceEntryName::ID(language) = language {
+                    entries.push((name.clone(), *language as LANGID));
+                }
+            }
+        }
+        Ok(entries)
+    }
+
+    /// Get the resource data with the given type, name and language.
+    /// The `kind` is used to describe the resource in the returned error.
+    pub(crate) fn data_entry(
+        &self, type_: &ResourceEntryName, name: &ResourceEntryName, language: LANGID, kind: &str,
+    ) -> Result<Option<&ResourceData>, ResourceError> {
+        let type_table = match self.root.table(type_, &format!("{} table", kind))? {
+            Some(table) => table,
+            None => return Ok(None),
+        };
+        let inner_table = match type_table.table(name, &format!("inner {} table", kind))? {
+            Some(table) => table,
+            None => return Ok(None),
+        };
+        match inner_table.get(ResourceEntryName::ID(language as u32)) {
+            Some(ResourceEntry::Data(data)) => Ok(Some(data)),
+            Some(ResourceEntry::Table(_)) => {
+                Err(ResourceError::InvalidTable(format!("{} table entry is not data", kind)))
+            }
+            None => Ok(None),
+        }
+    }
+
+    /// Set the resource data with the given type, name and language.
+    /// The codepage of existing data is kept.
+    /// The `kind` is used to describe the resource in the returned error.
+    pub(crate) fn set_data_entry(
+        &mut self, type_: &ResourceEntryName, name: &ResourceEntryName, language: LANGID,
+        data: Vec<u8>, kind: &str,
+    ) -> Result<(), ResourceError> {
+        let type_table = self.root.table_or_insert(type_, &format!("{} table", kind))?;
+        let inner_table = type_table.table_or_insert(name, &format!("inner {} table", kind))?;
+        match inner_table.get_mut(ResourceEntryName::ID(language as u32)) {
+            Some(ResourceEntry::Data(entry)) => entry.set_data(data),
+            Some(ResourceEntry::Table(_)) => {
+                return Err(ResourceError::InvalidTable(format!(
+                    "{} table entry is not data",
+                    kind
+                )));
+            }
+            None => {
+                inner_table.insert(
+                    ResourceEntryName::ID(language as u32),
+                    ResourceEntry::Data(ResourceData {
+                        data:     data.into(),
+                        codepage: CODE_PAGE_ID_EN_US as u32,
+                        reserved: 0,
+                    }),
+                );
+            }
+        }
+        Ok(())
+    }
+
+    /// Remove the resource data with the given type, name and language.
+    /// Tables that are empty after the removal are removed as well.
+    /// The `kind` is used to describe the resource in the returned error.
+    pub(crate) fn remove_data_entry(
+        &mut self, type_: &ResourceEntryName, name: &ResourceEntryName, language: LANGID,
+        kind: &str,
+    ) -> Result<Option<ResourceData>, ResourceError> {
+        let type_table = match self.root.table_mut(type_, &format!("{} table", kind))? {
+            Some(table) => table,
+            None => return Ok(None),
+        };
+        let inner_table = match type_table.table_mut(name, &format!("inner {} table", kind))? {
+            Some(table) => table,
+            None => return Ok(None),
+        };
+        let removed = match inner_table.get(ResourceEntryName::ID(language as u32)) {
+            Some(ResourceEntry::Table(_)) => {
+                return Err(ResourceError::InvalidTable(format!(
+                    "{} table entry is not data",
+                    kind
+                )));
+            }
+            Some(ResourceEntry::Data(_)) => inner_table
+                .remove(ResourceEntryName::ID(language as u32))
+                .and_then(|entry| match entry {
+                    ResourceEntry::Data(data) => Some(data),
+                    ResourceEntry::Table(_) => None,
+                }),
+            None => return Ok(None),
+        };
+        if inner_table.entries.is_empty() {
+            type_table.remove(name);
+        }
+        if type_table.entries.is_empty() {
+            self.root.remove(type_);
+        }
+        Ok(removed)
+    }
+
+    /// Get the resource data at the given path.
+    ///
+    /// # Returns
+    /// Returns `None` if the resource does not exist.
+    /// Returns an error if the resource table structure is not well-formed.
+    pub fn get_resource(
+        &self, path: &ResourcePath,
+    ) -> Result<Option<&ResourceData>, ResourceError> {
+        self.data_entry(&(&path.type_).into(), &path.name, path.language, "resource")
+    }
+
+    /// Find the resource data with the given type and name, falling back to other languages like `FindResourceEx`.
+    /// The languages are tried in this order:
+    /// 1. the exact `language`
+    /// 2. the primary language of `language` with [`SUBLANG_NEUTRAL`]
+    /// 3. [`LANG_NEUTRAL`]
+    /// 4. the `user_language`, if given
+    /// 5. [`LANGUAGE_ID_EN_US`]
+    /// 6. the first available language
+    ///
+    /// # Returns
+    /// Returns the path with the chosen language and the resource data.
+    /// Returns `None` if no resource with the given type and name exists.
+    /// Returns an error if the resource table structure is not well-formed.
+    pub fn find_resource<T: Into<ResourceType>, N: Into<ResourceEntryName>>(
+        &self, type_: T, name: N, language: LANGID, user_language: Option<LANGID>,
+    ) -> Result<Option<(ResourcePath, &ResourceData)>, ResourceError> {
+        let type_ = type_.into();
+        let name = name.into();
+        let type_table = match self.root.table(ResourceEntryName::from(&type_), "resource table")? {
+            Some(table) => table,
+            None => return Ok(None),
+        };
+        let inner_table = match type_table.table(&name, "inner resource table")? {
+            Some(table) => table,
+            None => return Ok(None),
+        };
+
+        let primary_language = (language & 0x3ff) | (SUBLANG_NEUTRAL << 10);
+        let fallbacks = [
+            Some(language),
+            Some(primary_language),
+            Some(LANG_NEUTRAL),
+            user_language,
+        ];
+        let languages = fallbacks.into_iter().flatten().chain([LANGUAGE_ID_EN_US]);
+        let first_language = inner_table.entries.keys().find_map(|language| match language {
+            ResourceEntryName::ID(language) => Some(*language as LANGID),
+            ResourceEntryName::Name(_) => None,
+        });
+        for language in languages.chain(first_language) {
+            match inner_table.get(ResourceEntryName::ID(language as u32)) {
+                Some(ResourceEntry::Data(data)) => {
+                    return Ok(Some((ResourcePath::new(type_, name, language), data)));
+                }
+                Some(ResourceEntry::Table(_)) => {
+                    return Err(ResourceError::InvalidTable(
+                        "resource table entry is not data".to_string(),
+                    ));
+                }
+                None => {}
+            }
+        }
+        Ok(None)
+    }
+
+    /// Change the language of all resources from one language to another, e.g. from [`LANG_NEUTRAL`] to [`LANGUAGE_ID_EN_US`].
+    /// The `conflict` policy decides what happens if a resource already exists in the target language.
+    ///
+    /// # Returns
+    /// Returns the number of resources that were changed.
+    /// Returns an error if the resource table structure is not well-formed
+    /// or if a conflict occurs with [`ConflictPolicy::Error`], in which case no resource is changed.
+    pub fn retag_language(
+        &mut self, from: LANGID, to: LANGID, conflict: ConflictPolicy,
+    ) -> Result<usize, ResourceError> {
+        let from = ResourceEntryName::ID(from as u32);
+        let to = ResourceEntryName::ID(to as u32);
+        if from == to {
+            return Ok(0);
+        }
+
+        let mut paths = Vec::new();
+        for (type_, type_entry) in self.root.entries.iter() {
+            let type_table = type_entry.as_table().ok_or_else(|| {
+                ResourceError::InvalidTable("resource table is not a table".to_string())
+            })?;
+            for (name, name_entry) in type_table.entries.iter() {
+                let inner_table = name_entry.as_table().ok_or_else(|| {
+                    ResourceError::InvalidTable("inner resource table is not a table".to_string())
+                })?;
+                if !inner_table.entries.contains_key(&from) {
+                    continue;
+                }
+                if inner_table.entries.contains_key(&to) {
+                    match conflict {
+                        ConflictPolicy::Skip => continue,
+                        ConflictPolicy::Replace => {}
+                        ConflictPolicy::Error => {
+                            let path = ResourcePath::new(type_, name, to.id() as LANGID);
+                            return Err(ResourceError::LanguageConflict(format!(
+                                "{} already exists",
+                                path
+                            )));
+                        }
+                    }
+                }
+                paths.push((type_.clone(), name.clone()));
+            }
+        }
+
+        for (type_, name) in &paths {
+            let inner_table = self.root.table_mut(type_, "resource table")?.unwrap();
+            let inner_table = inner_table.table_mut(name, "inner resource table")?.unwrap();
+            inner_table.rename(&from, to.clone());
+        }
+        Ok(paths.len())
+    }
+
+    /// Remove all resources with the given language.
+    /// Tables that are empty after the removal are removed as well.
+    ///
+    /// # Returns
+    /// Returns the number of removed resources.
+    /// Returns an error if the resource table structure is not well-formed, in which case no resource is removed.
+    pub fn remove_language(&mut self, language: LANGID) -> Result<usize, ResourceError> {
+        let paths = self
+            .resources()
+            .map(|(path, _)| path)
+            .filter(|path| path.language == language)
+            .collect::<Vec<_>>();
+        for path in &paths {
+            self.remove_resource(path)?;
+        }
+        Ok(paths.len())
+    }
+
+    /// Set the resource data at the given path.
+    /// Missing tables are created and the codepage of existing data is kept.
+    ///
+    /// # Returns
+    /// Returns an error if the resource table structure is not well-formed.
+    pub fn set_resource(
+        &mut self, path: &ResourcePath, data: Vec<u8>,
+    ) -> Result<(), ResourceError> {
+        self.set_data_entry(&(&path.type_).into(), &path.name, path.language, data, "resource")
+    }
+
+    /// Remove the resource data at the given path.
+    /// Tables that are empty after the removal are removed as well.
+    ///
+    /// # Returns
+    /// Returns the removed resource data or `None` if it did not exist.
+    /// Returns an error if the resource table structure is not well-formed.
+    pub fn remove_resource(
+        &mut self, path: &ResourcePath,
+    ) -> Result<Option<ResourceData>, ResourceError> {
+        self.remove_data_entry(&(&path.type_).into(), &path.name, path.language, "resource")
+    }
+
+    /// Returns an iterator over all resource data with their paths.
+    /// Entries that do not follow the type, name and language structure are skipped.
+    pub fn resources(&self) -> impl Iterator<Item = (ResourcePath, &ResourceData)> + '_ {
+        self.root.entries.iter().flat_map(|(type_, type_entry)| {
+            let type_table = type_entry.as_table().into_iter();
+            type_table
+                .flat_map(|table| table.entries.iter())
+                .flat_map(move |(name, entry)| {
+                    let name_table = entry.as_table().into_iter();
+                    name_table.flat_map(|table| table.entries.iter()).filter_map(
+                        move |(language, entry)| match (language, entry) {
+                            (ResourceEntryName::ID(language), ResourceEntry::Data(data)) => Some((
+                                ResourcePath::new(type_.clone(), name, *language as LANGID),
+                                data,
+                            )),
+                            _ => None,
+                        },
+                    )
+                })
+        })
+    }
+
+    /// Returns an iterator over all mutable resource data with their paths.
+    /// Entries that do not follow the type, name and language structure are skipped.
+    pub fn resources_mut(
+        &mut self,
+    ) -> impl Iterator<Item = (ResourcePath, &mut ResourceData)> + '_ {
+        self.root.entries.iter_mut().flat_map(|(type_, type_entry)| {
+            let type_table = type_entry.as_table_mut().into_iter();
+            type_table
+                .flat_map(|table| table.entries.iter_mut())
+                .flat_map(move |(name, entry)| {
+                    let name_table = entry.as_table_mut().into_iter();
+                    name_table.flat_map(|table| table.entries.iter_mut()).filter_map(
+                        move |(language, entry)| match (language, entry) {
+                            (ResourceEntryName::ID(language), ResourceEntry::Data(data)) => Some((
+                                ResourcePath::new(type_.clone(), name, *language as LANGID),
+                                data,
+                            )),
+                            _ => None,
+                        },
+                    )
+                })
+        })
+    }
+
     /// Returns the virtual address of the resource directory in the source image.
     pub fn virtual_address(&self) -> u32 { self.virtual_address }
 
@@ -825,6 +1129,9 @@ enum TableData {
 }
 
 /// Portable executable resource table.
+///
+/// Entries can be looked up by anything that converts into a [`ResourceEntryName`],
+/// such as a [`ResourceType`], a `u16` ID or a `&str` name.
 #[derive(Debug, Clone, Eq, PartialEq, Default)]
 pub struct ResourceTable {
     pub(crate) data:    ResourceDirectoryTable,
@@ -964,16 +1271,7 @@ impl ResourceTable {
         // Sort entries as described in <https://learn.microsoft.com/en-us/windows/win32/debug/pe-format#resource-directory-entries>
         // named entries (case-insensitive) then ID entries (numerical)
         let mut sorted_keys: Vec<&ResourceEntryName> = self.entries.keys().collect();
-        sorted_keys.sort_by(|a, b| match (a, b) {
-            (ResourceEntryName::Name(_), ResourceEntryName::ID(_)) => Ordering::Less,
-            (ResourceEntryName::ID(_), ResourceEntryName::Name(_)) => Ordering::Greater,
-            (ResourceEntryName::Name(_), ResourceEntryName::Name(_)) => a
-                .to_string()
-                .unwrap()
-                .to_uppercase()
-                .cmp(&b.to_string().unwrap().to_uppercase()),
-            (ResourceEntryName::ID(a), ResourceEntryName::ID(b)) => a.cmp(b),
-        });
+        sorted_keys.sort();
 
         let mut next_table_offset = 0u32;
         let mut next_table_sizes = 0u32;
@@ -1062,28 +1360,29 @@ impl ResourceTable {
     /// Get a resource entry from the table.
     /// # Returns
     /// The resource entry.
-    pub fn get<N: Borrow<ResourceEntryName>>(&self, name: N) -> Option<&ResourceEntry> {
-        self.entries.get(name.borrow())
+    pub fn get<N: Into<ResourceEntryName>>(&self, name: N) -> Option<&ResourceEntry> {
+        self.entries.get(&name.into())
     }
 
     /// Get a mutable resource entry from the table.
     /// # Returns
     /// The resource entry.
-    pub fn get_mut<N: Borrow<ResourceEntryName>>(&mut self, name: N) -> Option<&mut ResourceEntry> {
-        self.entries.get_mut(name.borrow())
+    pub fn get_mut<N: Into<ResourceEntryName>>(&mut self, name: N) -> Option<&mut ResourceEntry> {
+        self.entries.get_mut(&name.into())
     }
 
     /// Insert a resource entry into the table.
     /// If an entry with the given name already exists, it will be replaced.
     /// # Returns
     /// The replaced entry.
-    pub fn insert<N: Borrow<ResourceEntryName>>(
+    pub fn insert<N: Into<ResourceEntryName>>(
         &mut self, name: N, entry: ResourceEntry,
     ) -> Option<ResourceEntry> {
-        let name = name.borrow();
-        let entry = self.entries.insert(name.clone(), entry);
+        let name = name.into();
+        let is_name = name.string_size() > 0;
+        let entry = self.entries.insert(name, entry);
         if entry.is_none() {
-            if name.string_size() > 0 {
+            if is_name {
                 self.data.number_of_name_entries += 1;
             } else {
                 self.data.number_of_id_entries += 1;
@@ -1096,12 +1395,12 @@ impl ResourceTable {
     /// If an entry with the given name already exists, it will be replaced.
     /// # Returns
     /// The replaced entry.
-    pub fn insert_at<N: Borrow<ResourceEntryName>>(
+    pub fn insert_at<N: Into<ResourceEntryName>>(
         &mut self, name: N, entry: ResourceEntry, position: usize,
     ) -> Option<ResourceEntry> {
-        let name = name.borrow();
+        let name = name.into();
         let len = self.entries.len();
-        let old_entry = self.entries.get(name).cloned();
+        let old_entry = self.entries.get(&name).cloned();
         let new_entry = self
             .entries
             .entry(name.clone())
@@ -1122,9 +1421,9 @@ impl ResourceTable {
     /// Remove a resource entry from the table.
     /// # Returns
     /// The removed entry.
-    pub fn remove<N: Borrow<ResourceEntryName>>(&mut self, name: N) -> Option<ResourceEntry> {
-        let name = name.borrow();
-        if let Some(entry) = self.entries.swap_remove(name) {
+    pub fn remove<N: Into<ResourceEntryName>>(&mut self, name: N) -> Option<ResourceEntry> {
+        let name = name.into();
+        if let Some(entry) = self.entries.swap_remove(&name) {
             if name.string_size() > 0 {
                 self.data.number_of_name_entries -= 1;
             } else {
@@ -1136,6 +1435,103 @@ impl ResourceTable {
         }
     }
 
+    /// Get a child table from the table.
+    /// The `kind` is used to describe the table in the returned error.
+    pub(crate) fn table<N: Borrow<ResourceEntryName>>(
+        &self, name: N, kind: &str,
+    ) -> Result<Option<&ResourceTable>, ResourceError> {
+        match self.entries.get(name.borrow()) {
+            Some(ResourceEntry::Table(table)) => Ok(Some(table)),
+            Some(ResourceEntry::Data(_)) => {
+                Err(ResourceError::InvalidTable(format!("{} is not a table", kind)))
+            }
+            None => Ok(None),
+        }
+    }
+
+    /// Get a mutable child table from the table.
+    /// The `kind` is used to describe the table in the returned error.
+    pub(crate) fn table_mut<N: Borrow<ResourceEntryName>>(
+        &mut self, name: N, kind: &str,
+    ) -> Result<Option<&mut ResourceTable>, ResourceError> {
+        match self.entries.get_mut(name.borrow()) {
+            Some(ResourceEntry::Table(table)) => Ok(Some(table)),
+            Some(ResourceEntry::Data(_)) => {
+                Err(ResourceError::InvalidTable(format!("{} is not a table", kind)))
+            }
+            None => Ok(None),
+        }
+    }
+
+    /// Get a mutable child table from the table, inserting an empty table if it does not exist.
+    /// The `kind` is used to describe the table in the returned error.
+    pub(crate) fn table_or_insert<N: Borrow<ResourceEntryName>>(
+        &mut self, name: N, kind: &str,
+    ) -> Result<&mut ResourceTable, ResourceError> {
+        let name = name.borrow();
+        if self.get(name).is_none() {
+            self.insert(name, ResourceEntry::Table(ResourceTable::default()));
+        }
+        Ok(self.table_mut(name, kind)?.unwrap())
+    }
+
+    /// Rename an entry in the table, keeping its position.
+    /// An existing entry with the new name is replaced.
+    pub(crate) fn rename(&mut self, from: &ResourceEntryName, to: ResourceEntryName) {
+        if !self.entries.contains_key(from) {
+            return;
+        }
+        if let Some(index) = self.entries.get_index_of(&to) {
+            self.entries.shift_remove_index(index);
+            if to.string_size() > 0 {
+                self.data.number_of_name_entries -= 1;
+            } else {
+                self.data.number_of_id_entries -= 1;
+            }
+        }
+        let index = self.entries.get_index_of(from).unwrap();
+        let (_, entry) = self.entries.shift_remove_index(index).unwrap();
+        self.entries.shift_insert(index, to.clone(), entry);
+        match (from.string_size() > 0, to.string_size() > 0) {
+            (true, false) => {
+                self.data.number_of_name_entries -= 1;
+                self.data.number_of_id_entries += 1;
+            }
+            (false, true) => {
+                self.data.number_of_id_entries -= 1;
+                self.data.number_of_name_entries += 1;
+            }
+            _ => {}
+        }
+    }
+
+    /// Returns the first of `count` consecutive IDs following the highest ID entry in the table.
+    /// The `kind` is used to describe the table in the returned error.
+    ///
+    /// # Returns
+    /// Returns an error if the IDs do not fit into the 16-bit IDs of icon and cursor group directories.
+    pub(crate) fn next_free_ids(&self, count: usize, kind: &str) -> Result<u16, ResourceError> {
+        let first = self
+            .entries
+            .keys()
+            .filter_map(|k| match k {
+                ResourceEntryName::ID(id) => Some(*id as usize),
+                _ => None,
+            })
+            .max()
+            .unwrap_or(0)
+            + 1;
+        if first + count > u16::MAX as usize + 1 {
+            return Err(ResourceError::InvalidTable(format!(
+                "{} has no {} free ids after id {}",
+                kind,
+                count,
+                first - 1
+            )));
+        }
+        Ok(first as u16)
+    }
+
     /// Returns the entries in the table.
     pub fn entries(&self) -> Vec<&ResourceEntryName> { self.entries.keys().collect() }
 
@@ -1171,9 +1567,9 @@ impl ResourceTable {
 /// Raw resource data.
 #[derive(Debug, Clone, Eq, PartialEq)]
 pub struct ResourceData {
-    data:     DebugIgnore<Vec<u8>>,
-    codepage: u32,
-    reserved: u32,
+    pub(crate) data:     DebugIgnore<Vec<u8>>,
+    pub(crate) codepage: u32,
+    pub(crate) reserved: u32,
 }
 impl Default for ResourceData {
     fn default() -> Self {
@@ -1324,27 +1720,52 @@ impl ResourceEntryName {
         }
     }
 
+    /// Create a name from a string.
+    ///
+    /// # Panics
+    /// Panics if the name is longer than `u16::MAX` UTF-16 code units.
     pub fn from_string<S: AsRef<str>>(string: S) -> Self {
-        let string = string.as_ref();
-        let mut data = Vec::with_capacity(string.len() * 2 + 2);
-        data.extend_from_slice(&(string.len() as u16).to_le_bytes());
-        data.extend(string.encode_utf16().flat_map(|c| c.to_le_bytes()));
-        Self::Name(data)
+        Self::from_utf16(&string.as_ref().encode_utf16().collect::<Vec<_>>())
+            .expect("resource name is too long")
+    }
+
+    /// Create a name from UTF-16 code units.
+    /// Unpaired surrogates are kept as they are.
+    ///
+    /// # Returns
+    /// Returns an error if the name is longer than `u16::MAX` code units.
+    pub fn from_utf16(units: &[u16]) -> Result<Self, ReadError> {
+        let length = u16::try_from(units.len())
+            .map_err(|_| ReadError(format!("resource name is too long: {} units", units.len())))?;
+        let mut data = Vec::with_capacity(units.len() * 2 + 2);
+        data.extend_from_slice(&length.to_le_bytes());
+        data.extend(units.iter().flat_map(|c| c.to_le_bytes()));
+        Ok(Self::Name(data))
     }
 
+    /// Get the name as a string.
+    /// Unpaired surrogates are replaced with `U+FFFD`, use [`to_utf16`](Self::to_utf16) to get the exact name.
+    ///
+    /// # Returns
+    /// Returns `None` if this is an ID.
     pub fn to_string(&self) -> Option<String> {
+        self.to_utf16().map(|units| String::from_utf16_lossy(&units))
+    }
+
+    /// Get the name as UTF-16 code units.
+    ///
+    /// # Returns
+    /// Returns `None` if this is an ID.
+    pub fn to_utf16(&self) -> Option<Vec<u16>> {
         match self {
             Self::ID(_) => None,
-            Self::Name(data) => {
-                let length = read::<u16>(&data[0..]).unwrap() as usize;
-                let data = &data[2..];
-                let mut string = String::with_capacity(length);
-                for i in 0..length {
-                    let c = read::<u16>(&data[i * 2..]).unwrap() as u32;
-                    string.push(char::from_u32(c).unwrap_or(char::REPLACEMENT_CHARACTER));
-                }
-                Some(string)
-            }
+            Self::Name(data) => Some(
+                data.get(2..)
+                    .unwrap_or_default()
+                    .chunks_exact(2)
+                    .map(|c| u16::from_le_bytes([c[0], c[1]]))
+                    .collect(),
+            ),
         }
     }
 
@@ -1369,272 +1790,268 @@ impl ResourceEntryName {
         }
     }
 }
-
-/// Version string table.
-/// This is an entry in the version info resource.
-#[derive(Debug, Clone, Eq, PartialEq, Default)]
-pub struct VersionStringTable {
-    pub key:     String,
-    pub strings: IndexMap<String, String, RandomState>,
+impl From<u16> for ResourceEntryName {
+    fn from(id: u16) -> Self { Self::ID(id as u32) }
 }
-
-/// Version info resource.
-/// This is a special resource that contains the version information of the executable.
-#[derive(Debug, Clone, Eq, PartialEq, Default)]
-pub struct VersionInfo {
-    pub info:    FixedFileInfo,
-    pub strings: Vec<VersionStringTable>,
-    pub vars:    Vec<VersionU16>,
+impl From<&str> for ResourceEntryName {
+    /// Create a name from a string.
+    /// Use [`str::parse`] to interpret `#123` as an ID.
+    fn from(name: &str) -> Self { Self::from_string(name) }
 }
-impl VersionInfo {
-    /// Parse the version info resource from a byte slice.
-    ///
-    /// # Returns
-    /// Returns an error if the version info resource is not well-formed.
-    pub fn parse(data: &[u8]) -> Result<Self, ReadError> {
-        // read the version root header
-        let header = read::<VersionHeader>(data)?;
-        if header.length as usize > data.len() {
-            return Err(ReadError(format!(
-                "version root length {:#x?} is larger than data length {:#x?}",
-                header.length,
-                data.len()
-            )));
-        }
-
-        let version_root_key =
-            read_u16_string(&data[size_of::<VersionHeader>()..size_of::<VersionHeader>() + 32])?;
-        if version_root_key != "VS_VERSION_INFO" {
-            return Err(ReadError(format!("invalid version root key: {:?}", version_root_key)));
-        }
-        let value_offset =
-            aligned_to(size_of::<VersionHeader>() + version_root_key.len() * 2 + 2, 4);
-
-        // read the fixed file info
-        if header.value_length != size_of::<FixedFileInfo>() as u16 {
-            return Err(ReadError(format!(
-                "invalid file info length: {:#x?}",
-                header.value_length
-            )));
+impl From<&ResourceEntryName> for ResourceEntryName {
+    fn from(name: &ResourceEntryName) -> Self { name.clone() }
+}
+impl FromStr for ResourceEntryName {
+    type Err = ReadError;
+
+    /// Parse a name or an ID in the `#123` syntax used by `MAKEINTRESOURCE` strings.
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        match name.strip_prefix('#') {
+            Some(id) => id
+                .parse::<u16>()
+                .map(Self::from)
+                .map_err(|_| ReadError(format!("invalid resource id: {:?}", name))),
+            None if name.is_empty() => Err(ReadError("resource name is empty".to_string())),
+            None => Self::from_utf16(&name.encode_utf16().collect::<Vec<_>>()),
         }
-        let info = read::<FixedFileInfo>(&data[value_offset..])?;
-        if info.signature != 0xfeef04bd {
-            return Err(ReadError(format!(
-                "invalid fixed file info signature: {:#x?}",
-                info.signature
-            )));
+    }
+}
+impl fmt::Display for ResourceEntryName {
+    /// Formats IDs in the `#123` syntax and names as strings.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::ID(id) => write!(f, "#{}", id),
+            Self::Name(_) => {
+                let units = self.to_utf16().unwrap_or_default();
+                write!(f, "{}", String::from_utf16_lossy(&units))
+            }
         }
-        let file_info_header_offset = aligned_to(value_offset + size_of::<FixedFileInfo>(), 4);
-
-        let mut child_offset = file_info_header_offset;
-        let mut strings = Vec::new();
-        let mut vars = Vec::new();
-
-        while child_offset < data.len() {
-            // read the file info header
-            let file_info_header = read::<VersionHeader>(&data[child_offset..])?;
-            let file_info_end = child_offset + file_info_header.length as usize;
-            let file_info_key =
-                read_u16_string(&data[child_offset + size_of::<VersionHeader>()..file_info_end])?;
-            let mut tables_offset = aligned_to(
-                child_offset + size_of::<VersionHeader>() + file_info_key.len() * 2 + 2,
-                4,
-            );
-
-            match file_info_key.as_str() {
-                "VarFileInfo" => {
-                    let var_header = read::<VersionHeader>(&data[tables_offset..])?;
-                    let var_end = tables_offset + var_header.length as usize;
-                    let table_key = read_u16_string(
-                        &data[tables_offset + size_of::<VersionHeader>()..var_end],
-                    )?;
-                    if &table_key != "Translation" {
-                        return Err(ReadError(format!("invalid var table key: {:?}", table_key)));
-                    }
-                    let vars_offset = aligned_to(
-                        tables_offset + size_of::<VersionHeader>() + table_key.len() * 2 + 2,
-                        4,
-                    );
-                    let mut var_offset = vars_offset;
-                    while var_offset < var_end {
-                        vars.push(read::<VersionU16>(&data[var_offset..])?);
-                        var_offset += size_of::<u32>();
-                    }
-                }
-                "StringFileInfo" => {
-                    while tables_offset < child_offset + file_info_header.length as usize {
-                        let string_table_header = read::<VersionHeader>(&data[tables_offset..])?;
-                        let string_table_end = tables_offset + string_table_header.length as usize;
-                        let string_table_key = read_u16_string(
-                            &data[tables_offset + size_of::<VersionHeader>()..string_table_end],
-                        )?;
-                        let strings_offset = aligned_to(
-                            tables_offset
-                                + size_of::<VersionHeader>()
-                                + string_table_key.len() * 2
-                                + 2,
-                            4,
-                        );
-
-                        let mut string_offset = strings_offset;
-                        let mut string_table = VersionStringTable {
-                            key:     string_table_key,
-                            strings: IndexMap::default(),
-                        };
-
-                        while string_offset < string_table_end {
-                            let string_header = read::<VersionHeader>(&data[string_offset..])?;
-                            string_offset += size_of::<VersionHeader>();
-                            let string_key = read_u16_string(
-                                &data[string_offset..string_offset + string_header.length as usize],
-                            )?;
-                            string_offset = aligned_to(string_offset + string_key.len() * 2 + 2, 4);
-
-                            if string_header.value_length == 0 {
-                                continue;
-                            }
-                            if string_header.type_ == 1 {
-                                let string_value = read_u16_string(
-                                    &data[string_offset
-                                        ..string_offset + string_header.value_length as usize * 2],
-                                )?;
-                                string_table.strings.insert(string_key, string_value);
-                            } else {
-                                error!(
-                                    "invalid string value type: {:#x?} (expected 0x1)",
-                                    string_header.type_
-                                );
-                            };
-                            string_offset = aligned_to(
-                                string_offset + string_header.value_length as usize * 2,
-                                4,
-                            );
-                        }
-                        tables_offset = aligned_to(string_table_end, 4);
-                        strings.push(string_table);
-                    }
-                }
-                _ => {
-                    return Err(ReadError(format!(
-                        "invalid version string key: {:?}",
-                        file_info_key
-                    )));
-                }
+    }
+}
+impl Ord for ResourceEntryName {
+    /// Orders names before IDs as in the resource directory.
+    /// Names are compared case-insensitively, IDs numerically.
+    fn cmp(&self, other: &Self) -> Ordering {
+        match (self, other) {
+            (Self::Name(_), Self::ID(_)) => Ordering::Less,
+            (Self::ID(_), Self::Name(_)) => Ordering::Greater,
+            (Self::Name(a), Self::Name(b)) => {
+                let upper = |name: &Self| {
+                    let units = name.to_utf16().unwrap_or_default();
+                    String::from_utf16_lossy(&units).to_uppercase()
+                };
+                upper(self).cmp(&upper(other)).then_with(|| a.cmp(b))
             }
-            child_offset = aligned_to(child_offset + file_info_header.length as usize, 4);
+            (Self::ID(a), Self::ID(b)) => a.cmp(b),
         }
-
-        Ok(Self {
-            info,
-            strings,
-            vars,
-        })
     }
+}
+impl PartialOrd for ResourceEntryName {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> { Some(self.cmp(other)) }
+}
 
-    /// Build the version info into raw bytes to be included in a resource table.
-    pub fn build(&self) -> Vec<u8> {
-        let mut data = Vec::new();
-
-        let mut string_tables = Vec::new();
-        for string_table_data in &self.strings {
-            let mut string_table_children = Vec::new();
-            for (key, value) in &string_table_data.strings {
-                let mut string = Vec::new();
-                string.extend(
-                    VersionHeader {
-                        length:       ((aligned_to(6 + key.len() * 2 + 2, 4) + value.len() * 2 + 2)
-                            as u16),
-                        value_length: value.len() as u16 + 1,
-                        type_:        1,
-                    }
-                    .as_bytes(),
-                );
-                string.extend(string_to_u16(key));
-                string.extend(iter::repeat(0).take(aligned_to(string.len(), 4) - string.len()));
-                string.extend(string_to_u16(value));
-                string.extend(iter::repeat(0).take(aligned_to(string.len(), 4) - string.len()));
-                string_table_children.extend(string);
-            }
-            let mut string_table = Vec::new();
-            string_table.extend(
-                VersionHeader {
-                    length:       (aligned_to(6 + string_table_data.key.len() * 2 + 2, 4)
-                        + string_table_children.len()) as u16,
-                    value_length: 0,
-                    type_:        1,
-                }
-                .as_bytes(),
-            );
-            string_table.extend(string_to_u16(&string_table_data.key));
-            string_table.extend(
-                iter::repeat(0).take(aligned_to(string_table.len(), 4) - string_table.len()),
-            );
-            string_table.extend(string_table_children);
-            string_tables.extend(string_table);
+/// Path of resource data in the resource directory.
+/// This is the type, name and language of the data.
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct ResourcePath {
+    pub type_:    ResourceType,
+    pub name:     ResourceEntryName,
+    pub language: LANGID,
+}
+impl ResourcePath {
+    /// Create a resource path.
+    pub fn new<T: Into<ResourceType>, N: Into<ResourceEntryName>>(
+        type_: T, name: N, language: LANGID,
+    ) -> Self {
+        Self {
+            type_: type_.into(),
+            name: name.into(),
+            language,
         }
+    }
+}
+impl fmt::Display for ResourcePath {
+    /// Formats the path as `type/name/language`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}/{}/{}", self.type_, self.name, self.language)
+    }
+}
 
-        let mut string_info = Vec::new();
-        string_info.extend(
-            VersionHeader {
-                length:       (aligned_to(6 + "StringFileInfo".len() * 2 + 2, 4)
-                    + string_tables.len()) as u16,
-                value_length: 0,
-                type_:        1,
-            }
-            .as_bytes(),
-        );
-        string_info.extend(string_to_u16("StringFileInfo"));
-        string_info
-            .extend(iter::repeat(0).take(aligned_to(string_info.len(), 4) - string_info.len()));
-        string_info.extend(string_tables);
-
-        let mut var = Vec::new();
-        var.extend(
-            VersionHeader {
-                length:       (aligned_to(6 + "Translation".len() * 2 + 2, 4) + self.vars.len() * 4)
-                    as u16,
-                value_length: (self.vars.len() * 4) as u16,
-                type_:        0,
-            }
-            .as_bytes(),
-        );
-        var.extend(string_to_u16("Translation"));
-        var.extend(iter::repeat(0).take(aligned_to(var.len(), 4) - var.len()));
-        var.extend(self.vars.iter().flat_map(|var| var.as_bytes()));
-        var.extend(iter::repeat(0).take(aligned_to(var.len(), 4) - var.len()));
-
-        let mut var_info = Vec::new();
-        var_info.extend(
-            VersionHeader {
-                length:       (aligned_to(6 + "VarFileInfo".len() * 2 + 2, 4) + var.len()) as u16,
-                value_length: 0,
-                type_:        1,
-            }
-            .as_bytes(),
-        );
-        var_info.extend(string_to_u16("VarFileInfo"));
-        var_info.extend(iter::repeat(0).take(aligned_to(var_info.len(), 4) - var_info.len()));
-        var_info.extend(var);
-
-        data.extend(
-            VersionHeader {
-                length:       (aligned_to(
-                    aligned_to(6 + "VS_VERSION_INFO".len() * 2 + 2, 4) + size_of::<FixedFileInfo>(),
-                    4,
-                ) + string_info.len()
-                    + var_info.len()) as u16,
-                value_length: size_of::<FixedFileInfo>() as u16,
-                type_:        0,
-            }
-            .as_bytes(),
-        );
-        data.extend(string_to_u16("VS_VERSION_INFO"));
-        data.extend(iter::repeat(0).take(aligned_to(data.len(), 4) - data.len()));
-        data.extend(self.info.as_bytes());
-        data.extend(iter::repeat(0).take(aligned_to(data.len(), 4) - data.len()));
-        data.extend(string_info);
-        data.extend(var_info);
+/// Policy for resources that already exist in the target language of [`ResourceDirectory::retag_language`].
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, Default)]
+pub enum ConflictPolicy {
+    /// Keep both the existing resource and the resource in the source language.
+    #[default]
+    Skip,
+    /// Replace the existing resource with the resource in the source language.
+    Replace,
+    /// Return an error without changing any resource.
+    Error,
+}
 
-        data
+/// Resource type.
+/// This is the name of an entry in the root table of the resource directory.
+///
+/// Standard types have an ID from the `RT_*` constants, other types are [`Custom`](ResourceType::Custom).
+/// Types are compared by their ID, so a [`Custom`](ResourceType::Custom) type with a standard ID equals the standard type.
+#[derive(Debug, Clone)]
+pub enum ResourceType {
+    Cursor,
+    Bitmap,
+    Icon,
+    Menu,
+    Dialog,
+    String,
+    FontDir,
+    Font,
+    Accelerator,
+    RCData,
+    MessageTable,
+    GroupCursor,
+    GroupIcon,
+    Version,
+    DlgInclude,
+    PlugPlay,
+    VXD,
+    AniCursor,
+    AniIcon,
+    HTML,
+    Manifest,
+    Custom(ResourceEntryName),
+}
+impl ResourceType {
+    /// Get the resource type ID.
+    ///
+    /// # Returns
+    /// Returns `None` if this is a custom type with a name.
+    pub fn id(&self) -> Option<u32> {
+        let id = match self {
+            Self::Cursor => RT_CURSOR,
+            Self::Bitmap => RT_BITMAP,
+            Self::Icon => RT_ICON,
+            Self::Menu => RT_MENU,
+            Self::Dialog => RT_DIALOG,
+            Self::String => RT_STRING,
+            Self::FontDir => RT_FONTDIR,
+            Self::Font => RT_FONT,
+            Self::Accelerator => RT_ACCELERATOR,
+            Self::RCData => RT_RCDATA,
+            Self::MessageTable => RT_MESSAGETABLE,
+            Self::GroupCursor => RT_GROUP_CURSOR,
+            Self::GroupIcon => RT_GROUP_ICON,
+            Self::Version => RT_VERSION,
+            Self::DlgInclude => RT_DLGINCLUDE,
+            Self::PlugPlay => RT_PLUGPLAY,
+            Self::VXD => RT_VXD,
+            Self::AniCursor => RT_ANICURSOR,
+            Self::AniIcon => RT_ANIICON,
+            Self::HTML => RT_HTML,
+            Self::Manifest => RT_MANIFEST,
+            Self::Custom(ResourceEntryName::ID(id)) => return Some(*id),
+            Self::Custom(ResourceEntryName::Name(_)) => return None,
+        };
+        Some(id as u32)
+    }
+}
+impl PartialEq for ResourceType {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (
+                Self::Custom(ResourceEntryName::Name(a)),
+                Self::Custom(ResourceEntryName::Name(b)),
+            ) => a == b,
+            _ => self.id().is_some() && self.id() == other.id(),
+        }
+    }
+}
+impl Eq for ResourceType {}
+impl Hash for ResourceType {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        match self {
+            Self::Custom(ResourceEntryName::Name(name)) => name.hash(state),
+            type_ => type_.id().hash(state),
+        }
+    }
+}
+impl From<ResourceEntryName> for ResourceType {
+    fn from(name: ResourceEntryName) -> Self {
+        let id = match name {
+            ResourceEntryName::ID(id) if id <= u16::MAX as u32 => id as u16,
+            name => return Self::Custom(name),
+        };
+        match id {
+            RT_CURSOR => Self::Cursor,
+            RT_BITMAP => Self::Bitmap,
+            RT_ICON => Self::Icon,
+            RT_MENU => Self::Menu,
+            RT_DIALOG => Self::Dialog,
+            RT_STRING => Self::String,
+            RT_FONTDIR => Self::FontDir,
+            RT_FONT => Self::Font,
+            RT_ACCELERATOR => Self::Accelerator,
+            RT_RCDATA => Self::RCData,
+            RT_MESSAGETABLE => Self::MessageTable,
+            RT_GROUP_CURSOR => Self::GroupCursor,
+            RT_GROUP_ICON => Self::GroupIcon,
+            RT_VERSION => Self::Version,
+            RT_DLGINCLUDE => Self::DlgInclude,
+            RT_PLUGPLAY => Self::PlugPlay,
+            RT_VXD => Self::VXD,
+            RT_ANICURSOR => Self::AniCursor,
+            RT_ANIICON => Self::AniIcon,
+            RT_HTML => Self::HTML,
+            RT_MANIFEST => Self::Manifest,
+            id => Self::Custom(ResourceEntryName::from(id)),
+        }
+    }
+}
+impl From<&ResourceEntryName> for ResourceType {
+    fn from(name: &ResourceEntryName) -> Self { name.clone().into() }
+}
+impl From<u16> for ResourceType {
+    fn from(id: u16) -> Self { ResourceEntryName::from(id).into() }
+}
+impl From<&str> for ResourceType {
+    fn from(name: &str) -> Self { ResourceEntryName::from(name).into() }
+}
+impl From<ResourceType> for ResourceEntryName {
+    fn from(type_: ResourceType) -> Self {
+        match type_ {
+            ResourceType::Custom(name) => name,
+            type_ => Self::ID(type_.id().unwrap()),
+        }
+    }
+}
+impl From<&ResourceType> for ResourceEntryName {
+    fn from(type_: &ResourceType) -> Self { type_.clone().into() }
+}
+impl fmt::Display for ResourceType {
+    /// Formats standard types by their `RT_*` constant name and custom types like [`ResourceEntryName`].
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Self::Cursor => "RT_CURSOR",
+            Self::Bitmap => "RT_BITMAP",
+            Self::Icon => "RT_ICON",
+            Self::Menu => "RT_MENU",
+            Self::Dialog => "RT_DIALOG",
+            Self::String => "RT_STRING",
+            Self::FontDir => "RT_FONTDIR",
+            Self::Font => "RT_FONT",
+            Self::Accelerator => "RT_ACCELERATOR",
+            Self::RCData => "RT_RCDATA",
+            Self::MessageTable => "RT_MESSAGETABLE",
+            Self::GroupCursor => "RT_GROUP_CURSOR",
+            Self::GroupIcon => "RT_GROUP_ICON",
+            Self::Version => "RT_VERSION",
+            Self::DlgInclude => "RT_DLGINCLUDE",
+            Self::PlugPlay => "RT_PLUGPLAY",
+            Self::VXD => "RT_VXD",
+            Self::AniCursor => "RT_ANICURSOR",
+            Self::AniIcon => "RT_ANIICON",
+            Self::HTML => "RT_HTML",
+            Self::Manifest => "RT_MANIFEST",
+            Self::Custom(name) => return write!(f, "{}", name),
+        };
+        f.write_str(name)
     }
 }
@@ -0,0 +1,192 @@
+//! String table resources.
+//!
+//! String tables are stored in `RT_STRING` resources as blocks of 16 length-prefixed UTF-16 strings.
+//! The string with ID `n` is stored in block `n / 16 + 1` at index `n % 16`.
+//! See <https://learn.microsoft.com/en-us/windows/win32/menurc/stringtable-resource> for more information.
+
+use alloc::{format, string::String, vec::Vec};
+
+use crate::{constants::*, errors::*, resource::*, util::*};
+
+/// Number of strings in a string table block.
+pub const STRING_TABLE_BLOCK_SIZE: usize = 16;
+
+/// String table block resource.
+/// This is a single `RT_STRING` resource entry containing 16 strings.
+///
+/// Empty strings are treated as absent, matching the behavior of `LoadString`.
+#[derive(Debug, Clone, Eq, PartialEq, Default)]
+pub struct StringTableBlock {
+    pub strings: [String; STRING_TABLE_BLOCK_SIZE],
+}
+impl StringTableBlock {
+    /// Returns the block ID containing the string with the given ID.
+    pub const fn block_id(string_id: u16) -> u16 { string_id / STRING_TABLE_BLOCK_SIZE as u16 + 1 }
+
+    /// Returns the index of the string with the given ID inside its block.
+    pub const fn block_index(string_id: u16) -> usize {
+        string_id as usize % STRING_TABLE_BLOCK_SIZE
+    }
+
+    /// Parse a string table block from a byte slice.
+    ///
+    /// # Returns
+    /// Returns an error if the block is truncated or contains invalid UTF-16.
+    pub fn parse(data: &[u8]) -> Result<Self, ReadError> {
+        let mut block = Self::default();
+        let mut offset = 0;
+        for (i, string) in block.strings.iter_mut().enumerate() {
+            let length = read::<u16>(&data[offset.min(data.len())..]).map_err(|_| {
+                ReadError(format!("string table block is truncated at string {}", i))
+            })? as usize;
+            offset += 2;
+            if offset + length * 2 > data.len() {
+                return Err(ReadError(format!("string {} of length {} is truncated", i, length)));
+            }
+            let units = data[offset..offset + length * 2]
+                .chunks_exact(2)
+                .map(|c| u16::from_le_bytes([c[0], c[1]]))
+                .collect::<Vec<_>>();
+            *string = String::from_utf16(&units)
+                .map_err(|_| ReadError(format!("string {} is not valid utf-16", i)))?;
+            offset += length * 2;
+        }
+        Ok(block)
+    }
+
+    /// Build the string table block into raw bytes to be included in a resource table.
+    ///
+    /// # Returns
+    /// Returns an error if a string is longer than `u16::MAX` UTF-16 code units.
+    pub fn build(&self) -> Result<Vec<u8>, ReadError> {
+        let mut data = Vec::new();
+        for (i, string) in self.strings.iter().enumerate() {
+            let length = string.encode_utf16().count();
+            let length = u16::try_from(length)
+                .map_err(|_| ReadError(format!("string {} of length {} is too long", i, length)))?;
+            data.extend(length.to_le_bytes());
+            data.extend(string.encode_utf16().flat_map(|c| c.to_le_bytes()));
+        }
+        Ok(data)
+    }
+
+    /// Returns if all strings in the block are empty.
+    pub fn is_empty(&self) -> bool { self.strings.iter().all(|string| string.is_empty()) }
+}
+
+impl ResourceDirectory {
+    /// Get a string from the string table.
+    ///
+    /// # Returns
+    /// Returns `None` if the string does not exist or is empty.
+    /// Returns an error if the string table resource directory is invalid.
+    pub fn get_string(&self, id: u16, language: LANGID) -> Result<Option<String>, ResourceError> {
+        let block = match self.get_string_table_block(StringTableBlock::block_id(id), language)? {
+            Some(block) => block,
+            None => return Ok(None),
+        };
+        let string = &block.strings[StringTableBlock::block_index(id)];
+        if string.is_empty() {
+            return Ok(None);
+        }
+        Ok(Some(string.clone()))
+    }
+
+    /// Set a string in the string table.
+    ///
+    /// This will overwrite the existing string with the given ID and language.
+    /// Setting an empty string removes the string.
+    ///
+    /// # Returns
+    /// Returns an error if the resource table structure is not well-formed
+    /// or the string is longer than `u16::MAX` UTF-16 code units.
+    pub fn set_string(
+        &mut self, id: u16, language: LANGID, string: &str,
+    ) -> Result<(), ResourceError> {
+        let block_id = StringTableBlock::block_id(id);
+        let mut block = self.get_string_table_block(block_id, language)?.unwrap_or_default();
+        block.strings[StringTableBlock::block_index(id)] = string.into();
+        self.set_string_table_block(block_id, language, &block)
+    }
+
+    /// Remove a string from the string table.
+    /// The containing block is removed when it no longer contains any strings.
+    ///
+    /// # Returns
+    /// Returns the removed string, or `None` if the string did not exist.
+    /// Returns an error if the resource table structure is not well-formed.
+    pub fn remove_string(
+        &mut self, id: u16, language: LANGID,
+    ) -> Result<Option<String>, ResourceError> {
+        let block_id = StringTableBlock::block_id(id);
+        let mut block = match self.get_string_table_block(block_id, language)? {
+            Some(block) => block,
+            None => return Ok(None),
+        };
+        let string = core::mem::take(&mut block.strings[StringTableBlock::block_index(id)]);
+        self.set_string_table_block(block_id, language, &block)?;
+        if string.is_empty() {
+            return Ok(None);
+        }
+        Ok(Some(string))
+    }
+
+    /// Get a string table block.
+    ///
+    /// # Returns
+    /// Returns `None` if the block does not exist.
+    /// Returns an error if the string table resource directory or the block is invalid.
+    pub fn get_string_table_block(
+        &self, block_id: u16, language: LANGID,
+    ) -> Result<Option<StringTableBlock>, ResourceError> {
+        let data = self.data_entry(
+            &ResourceEntryName::ID(RT_STRING as u32),
+            &ResourceEntryName::ID(block_id as u32),
+            language,
+            "string",
+        )?;
+        match data {
+            Some(data) => Ok(Some(StringTableBlock::parse(data.data())?)),
+            None => Ok(None),
+        }
+    }
+
+    /// Set a string table block.
+    ///
+    /// This will overwrite the existing block with the given ID and language.
+    /// Setting an empty block removes the block.
+    ///
+    /// # Returns
+    /// Returns an error if the resource table structure is not well-formed
+    /// or a string is longer than `u16::MAX` UTF-16 code units.
+    pub fn set_string_table_block(
+        &mut self, block_id: u16, language: LANGID, block: &StringTableBlock,
+    ) -> Result<(), ResourceError> {
+        if block.is_empty() {
+            return self.remove_string_table_block(block_id, language);
+        }
+        self.set_data_entry(
+            &ResourceEntryName::ID(RT_STRING as u32),
+            &ResourceEntryName::ID(block_id as u32),
+            language,
+            block.build()?,
+            "string",
+        )
+    }
+
+    /// Remove a string table block.
+    ///
+    /// # Returns
+    /// Returns an error if the resource table structure is not well-formed.
+    pub fn remove_string_table_block(
+        &mut self, block_id: u16, language: LANGID,
+    ) -> Result<(), ResourceError> {
+        self.remove_data_entry(
+            &ResourceEntryName::ID(RT_STRING as u32),
+            &ResourceEntryName::ID(block_id as u32),
+            language,
+            "string",
+        )?;
+        Ok(())
+    }
+}
@@ -331,3 +331,172 @@ pub struct VersionHeader {
     pub value_length: u16,
     pub type_:        u16,
 }
+
+#[repr(C, packed(2))]
+#[derive(
+    Debug, Clone, Copy, Eq, PartialEq, Ord, PartialOrd, FromBytes, IntoBytes, Immutable, Default,
+)]
+pub struct DialogTemplateHeader {
+    pub style:    u32,
+    pub ex_style: u32,
+    pub count:    u16,
+    pub x:        i16,
+    pub y:        i16,
+    pub cx:       i16,
+    pub cy:       i16,
+}
+
+#[repr(C, packed(2))]
+#[derive(
+    Debug, Clone, Copy, Eq, PartialEq, Ord, PartialOrd, FromBytes, IntoBytes, Immutable, Default,
+)]
+pub struct DialogTemplateExHeader {
+    pub version:   u16,
+    pub signature: u16,
+    pub help_id:   u32,
+    pub ex_style:  u32,
+    pub style:     u32,
+    pub count:     u16,
+    pub x:         i16,
+    pub y:         i16,
+    pub cx:        i16,
+    pub cy:        i16,
+}
+
+#[repr(C, packed(2))]
+#[derive(
+    Debug, Clone, Copy, Eq, PartialEq, Ord, PartialOrd, FromBytes, IntoBytes, Immutable, Default,
+)]
+pub struct DialogItemTemplateHeader {
+    pub style:    u32,
+    pub ex_style: u32,
+    pub x:        i16,
+    pub y:        i16,
+    pub cx:       i16,
+    pub cy:       i16,
+    pub id:       u16,
+}
+
+#[repr(C, packed(2))]
+#[derive(
+    Debug, Clone, Copy, Eq, PartialEq, Ord, PartialOrd, FromBytes, IntoBytes, Immutable, Default,
+)]
+pub struct DialogItemTemplateExHeader {
+    pub help_id:  u32,
+    pub ex_style: u32,
+    pub style:    u32,
+    pub x:        i16,
+    pub y:        i16,
+    pub cx:       i16,
+    pub cy:       i16,
+    pub id:       u32,
+}
+
+#[repr(C, packed(2))]
+#[derive(
+    Debug, Clone, Copy, Eq, PartialEq, Ord, PartialOrd, FromBytes, IntoBytes, Immutable, Default,
+)]
+pub struct MenuHeader {
+    pub version:     u16,
+    pub header_size: u16,
+}
+
+#[repr(C, packed(2))]
+#[derive(
+    Debug, Clone, Copy, Eq, PartialEq, Ord, PartialOrd, FromBytes, IntoBytes, Immutable, Default,
+)]
+pub struct MenuExItemHeader {
+    pub type_: u32,
+    pub state: u32,
+    pub id:    u32,
+    pub flags: u16,
+}
+
+#[repr(C, packed(2))]
+#[derive(
+    Debug, Clone, Copy, Eq, PartialEq, Ord, PartialOrd, FromBytes, IntoBytes, Immutable, Default,
+)]
+pub struct AcceleratorTableEntry {
+    pub flags:   u16,
+    pub key:     u16,
+    pub id:      u16,
+    pub padding: u16,
+}
+
+#[repr(C, packed(4))]
+#[derive(
+    Debug, Clone, Copy, Eq, PartialEq, Ord, PartialOrd, FromBytes, IntoBytes, Immutable, Default,
+)]
+pub struct MessageResourceBlock {
+    pub low_id:            u32,
+    pub high_id:           u32,
+    pub offset_to_entries: u32,
+}
+
+#[repr(C, packed(2))]
+#[derive(
+    Debug, Clone, Copy, Eq, PartialEq, Ord, PartialOrd, FromBytes, IntoBytes, Immutable, Default,
+)]
+pub struct MessageResourceEntryHeader {
+    pub length: u16,
+    pub flags:  u16,
+}
+
+#[repr(C, packed(2))]
+#[derive(
+    Debug, Clone, Copy, Eq, PartialEq, Ord, PartialOrd, FromBytes, IntoBytes, Immutable, Default,
+)]
+pub struct CursorDirectoryEntry {
+    pub width:     u16,
+    pub height:    u16,
+    pub planes:    u16,
+    pub bit_count: u16,
+    pub bytes:     u32,
+    pub id:        u16,
+}
+
+#[repr(C, packed(2))]
+#[derive(
+    Debug, Clone, Copy, Eq, PartialEq, Ord, PartialOrd, FromBytes, IntoBytes, Immutable, Default,
+)]
+pub struct BitmapInfoHeader {
+    pub size:             u32,
+    pub width:            i32,
+    pub height:           i32,
+    pub planes:           u16,
+    pub bit_count:        u16,
+    pub compression:      u32,
+    pub size_image:       u32,
+    pub x_pels_per_meter: i32,
+    pub y_pels_per_meter: i32,
+    pub clr_used:         u32,
+    pub clr_important:    u32,
+}
+
+#[repr(C, packed(4))]
+#[derive(
+    Debug, Clone, Copy, Eq, PartialEq, Ord, PartialOrd, FromBytes, IntoBytes, Immutable, Default,
+)]
+pub struct AnimatedCursorHeader {
+    pub size:         u32,
+    pub frames:       u32,
+    pub steps:        u32,
+    pub width:        u32,
+    pub height:       u32,
+    pub bit_count:    u32,
+    pub planes:       u32,
+    pub display_rate: u32,
+    pub flags:        u32,
+}
+
+#[repr(C, packed(2))]
+#[derive(
+    Debug, Clone, Copy, Eq, PartialEq, Ord, PartialOrd, FromBytes, IntoBytes, Immutable, Default,
+)]
+pub struct BitmapFileHeader {
+    pub type_:     u16,
+    pub size:      u32,
+    pub reserved1: u16,
+    pub reserved2: u16,
+    pub off_bits:  u32,
+}
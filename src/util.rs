@@ -1,4 +1,5 @@
 use alloc::{
+    format,
     string::{String, ToString},
     vec::Vec,
 };
@@ -27,15 +28,14 @@ pub fn aligned_to<T: Add<Output = T> + Sub<Output = T> + Rem<Output = T> + Eq +
 }
 
 pub fn read_u16_string(data: &[u8]) -> Result<String, ReadError> {
-    let mut string = String::new();
-    for i in 0..(data.len() / 2) {
-        let c = read::<u16>(&data[i * 2..])?;
-        if c == 0 {
-            break;
-        }
-        string.push(char::from_u32(c as u32).unwrap_or(char::REPLACEMENT_CHARACTER));
-    }
-    Ok(string)
+    let units = data
+        .chunks_exact(2)
+        .map(|c| u16::from_le_bytes([c[0], c[1]]))
+        .take_while(|c| *c != 0);
+    // unpaired surrogates can't be represented in a string and would not round-trip
+    char::decode_utf16(units).collect::<Result<String, _>>().map_err(|e| {
+        ReadError(format!("unpaired surrogate {:#x} in utf-16 string", e.unpaired_surrogate()))
+    })
 }
 
 pub fn string_to_u16<S: AsRef<str>>(string: S) -> Vec<u8> {
@@ -45,3 +45,12 @@ pub fn string_to_u16<S: AsRef<str>>(string: S) -> Vec<u8> {
     data.extend([0, 0]);
     data
 }
+
+pub fn read_u16_string_terminated(data: &[u8]) -> Result<(String, usize), ReadError> {
+    let length = data
+        .chunks_exact(2)
+        .position(|c| c == [0, 0])
+        .ok_or_else(|| ReadError("unterminated utf-16 string".to_string()))?;
+    let string = read_u16_string(&data[..length * 2])?;
+    Ok((string, length * 2 + 2))
+}
@@ -0,0 +1,892 @@
+//! Version info resources.
+//!
+//! The version info is stored in an `RT_VERSION` resource containing a `VS_VERSIONINFO` structure.
+//! It consists of the fixed file info with numeric versions and flags, and string tables with the displayed values.
+//! See <https://learn.microsoft.com/en-us/windows/win32/menurc/vs-versioninfo> for more information.
+
+use alloc::{
+    format,
+    string::{String, ToString},
+    vec::Vec,
+};
+use core::{borrow::Borrow, iter, mem::size_of};
+
+use foldhash::fast::RandomState;
+use indexmap::{IndexMap, IndexSet};
+use log::trace;
+use zerocopy::IntoBytes;
+
+use crate::{constants::*, errors::*, resource::*, types::*, util::*};
+
+const VERSION_NODE_MAX_DEPTH: usize = 16;
+
+/// Version string table.
+/// This is an entry in the version info resource.
+#[derive(Debug, Clone, Eq, PartialEq, Default)]
+pub struct VersionStringTable {
+    pub key:     String,
+    pub strings: IndexMap<String, String, RandomState>,
+}
+
+/// Version info resource.
+/// This is a special resource that contains the version information of the executable.
+///
+/// A parsed version info keeps unknown nodes, the types of values and the padding of the resource,
+/// so that building an unmodified version info reproduces the original data.
+/// This layout is not considered when comparing version infos.
+#[derive(Debug, Clone, Default)]
+pub struct VersionInfo {
+    pub info:    FixedFileInfo,
+    pub strings: Vec<VersionStringTable>,
+    pub vars:    Vec<VersionU16>,
+    layout:      Option<VersionNode>,
+}
+impl PartialEq for VersionInfo {
+    fn eq(&self, other: &Self) -> bool {
+        self.info == other.info && self.strings == other.strings && self.vars == other.vars
+    }
+}
+impl Eq for VersionInfo {}
+impl VersionInfo {
+    /// Parse the version info resource from a byte slice.
+    /// String entries without a value are not included in the string tables, but kept in the layout.
+    ///
+    /// # Returns
+    /// Returns an error if the version info resource is not well-formed.
+    pub fn parse(data: &[u8]) -> Result<Self, ReadError> {
+        let root = VersionNode::parse(data, 0, 0)?;
+        if root.key != "VS_VERSION_INFO" {
+            return Err(ReadError(format!("invalid version root key: {:?}", root.key)));
+        }
+
+        // read the fixed file info
+        let info = match &root.value {
+            VersionValue::Binary(value) if value.len() == size_of::<FixedFileInfo>() => {
+                read::<FixedFileInfo>(value)?
+            }
+            VersionValue::Binary(value) => {
+                return Err(ReadError(format!("invalid file info length: {:#x?}", value.len())));
+            }
+            VersionValue::Text(_) => {
+                return Err(ReadError("fixed file info is not binary".to_string()));
+            }
+        };
+        if info.signature != VS_FIXEDFILEINFO_SIGNATURE {
+            return Err(ReadError(format!(
+                "invalid fixed file info signature: {:#x?}",
+                info.signature
+            )));
+        }
+
+        let mut strings = Vec::new();
+        let mut vars = Vec::new();
+        for file_info in &root.children {
+            match file_info.key.as_str() {
+                "StringFileInfo" => {
+                    for table in &file_info.children {
+                        let mut string_table = VersionStringTable {
+                            key:     table.key.clone(),
+                            strings: IndexMap::default(),
+                        };
+                        for string in &table.children {
+                            match &string.value {
+                                VersionValue::Text(value)
+                                    if string.is_string()
+                                        && string.text_length != TextLength::Empty
+                                        && !string_table.strings.contains_key(&string.key) =>
+                                {
+                                    string_table.strings.insert(string.key.clone(), value.clone());
+                                }
+                                _ => trace!("keeping unknown version string node {:?}", string.key),
+                            }
+                        }
+                        strings.push(string_table);
+                    }
+                }
+                "VarFileInfo" => {
+                    for var in &file_info.children {
+                        match (&var.key[..], &var.value) {
+                            ("Translation", VersionValue::Binary(value)) => {
+                                for translation in value.chunks_exact(size_of::<VersionU16>()) {
+                                    vars.push(read::<VersionU16>(translation)?);
+                                }
+                            }
+                            _ => trace!("keeping unknown version var node {:?}", var.key),
+                        }
+                    }
+                }
+                _ => trace!("keeping unknown version node {:?}", file_info.key),
+            }
+        }
+
+        Ok(Self {
+            info,
+            strings,
+            vars,
+            layout: Some(root),
+        })
+    }
+
+    /// Build the version info into raw bytes to be included in a resource table.
+    /// Unknown content and the layout of unchanged parts of a parsed version info are kept.
+    pub fn build(&self) -> Vec<u8> {
+        let mut root = match &self.layout {
+            Some(root) => root.clone(),
+            None => {
+                let mut root =
+                    VersionNode::new("VS_VERSION_INFO", VersionValue::Binary(Vec::new()));
+                root.children.push(VersionNode::new("StringFileInfo", VersionValue::empty()));
+                root.children.push(VersionNode::new("VarFileInfo", VersionValue::empty()));
+                root
+            }
+        };
+        root.value = VersionValue::Binary(self.info.as_bytes().to_vec());
+
+        // update the string tables
+        if !root.children.iter().any(|node| node.key == "StringFileInfo")
+            && !self.strings.is_empty()
+        {
+            root.children.push(VersionNode::new("StringFileInfo", VersionValue::empty()));
+        }
+        if let Some(string_info) =
+            root.children.iter_mut().find(|node| node.key == "StringFileInfo")
+        {
+            let mut tables = core::mem::take(&mut string_info.children);
+            for string_table in &self.strings {
+                let table = match tables.iter().position(|table| table.key == string_table.key) {
+                    Some(index) => tables.remove(index),
+                    None => VersionNode::new(&string_table.key, VersionValue::empty()),
+                };
+                string_info.children.push(table.with_strings(&string_table.strings));
+            }
+        }
+
+        // update the translations
+        if !root.children.iter().any(|node| node.key == "VarFileInfo") && !self.vars.is_empty() {
+            root.children.push(VersionNode::new("VarFileInfo", VersionValue::empty()));
+        }
+        if let Some(var_info) = root.children.iter_mut().find(|node| node.key == "VarFileInfo") {
+            let value = VersionValue::Binary(self.vars.as_slice().as_bytes().to_vec());
+            match var_info.children.iter_mut().find(|var| {
+                var.key == "Translation" && matches!(var.value, VersionValue::Binary(_))
+            }) {
+                Some(translation) => translation.value = value,
+                None => var_info.children.push(VersionNode::new("Translation", value)),
+            }
+        }
+
+        let mut data = Vec::new();
+        root.build(&mut data);
+        data
+    }
+}
+
+/// Value of a node in the version info resource.
+#[derive(Debug, Clone, Eq, PartialEq)]
+enum VersionValue {
+    /// Text value with the type `1`.
+    Text(String),
+    /// Binary value with the type `0`.
+    Binary(Vec<u8>),
+}
+impl VersionValue {
+    fn empty() -> Self { VersionValue::Text(String::new()) }
+}
+
+/// Unit of the value length of a text value.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+enum TextLength {
+    /// No value data and a value length of zero.
+    Empty,
+    /// Characters including the null terminator, which is the standard.
+    Chars,
+    /// Characters excluding the null terminator.
+    CharsWithoutNull,
+    /// Bytes including the null terminator.
+    Bytes,
+    /// Bytes excluding the null terminator.
+    BytesWithoutNull,
+    /// Value length that matches none of the units, which is kept while the text is unchanged.
+    Raw(u16),
+}
+
+/// Node in the version info resource.
+/// Every node has a header, a key, an optional value and child nodes, aligned to 32-bit boundaries.
+/// See <https://learn.microsoft.com/en-us/windows/win32/menurc/version-information> for more information.
+#[derive(Debug, Clone, Eq, PartialEq)]
+struct VersionNode {
+    key:         String,
+    value:       VersionValue,
+    children:    Vec<VersionNode>,
+    text_length: TextLength,
+    terminated:  bool,
+    padded:      bool,
+    /// Data after the children that is neither a node nor the alignment padding.
+    trailing:    Vec<u8>,
+}
+impl VersionNode {
+    /// Create a node with the standard layout.
+    /// Empty text values are written without data, as used by nodes that only contain children.
+    fn new(key: &str, value: VersionValue) -> Self {
+        let empty = value == VersionValue::empty();
+        Self {
+            key: key.to_string(),
+            value,
+            children: Vec::new(),
+            text_length: if empty {
+                TextLength::Empty
+            } else {
+                TextLength::Chars
+            },
+            terminated: !empty,
+            padded: false,
+            trailing: Vec::new(),
+        }
+    }
+
+    /// Parse a node at the start of the data.
+    /// The `offset` is the position of the data within the resource, which is used for the alignment.
+    fn parse(data: &[u8], offset: usize, depth: usize) -> Result<Self, ReadError> {
+        if depth > VERSION_NODE_MAX_DEPTH {
+            return Err(ReadError("version nodes are nested too deeply".to_string()));
+        }
+        let header = read::<VersionHeader>(data)?;
+        let length = header.length as usize;
+        if length < size_of::<VersionHeader>() || length > data.len() {
+            return Err(ReadError(format!(
+                "version node length {:#x?} is invalid for data length {:#x?}",
+                header.length,
+                data.len()
+            )));
+        }
+        let data = &data[..length];
+        let aligned = |position: usize| aligned_to(offset + position, 4) - offset;
+
+        let (key, key_size) = read_u16_string_terminated(&data[size_of::<VersionHeader>()..])?;
+        let value_start = aligned(size_of::<VersionHeader>() + key_size).min(length);
+        let value_length = header.value_length as usize;
+
+        let mut node = Self::new(&key, VersionValue::Binary(Vec::new()));
+        let value_end = match header.type_ {
+            0 => {
+                let value_end = value_start + value_length;
+                if value_end > length {
+                    return Err(ReadError(format!(
+                        "version value length {:#x?} of {:?} is out of bounds",
+                        header.value_length, key
+                    )));
+                }
+                node.value = VersionValue::Binary(data[value_start..value_end].to_vec());
+                value_end
+            }
+            1 if value_length == 0 => {
+                node.value = VersionValue::empty();
+                node.text_length = TextLength::Empty;
+                node.terminated = false;
+                value_start
+            }
+            1 => {
+                let limit = (value_start + value_length * 2 + 2).min(length);
+                let units = data[value_start..limit].chunks_exact(2);
+                let chars = units.clone().take_while(|c| *c != [0, 0]).count();
+                node.terminated = chars < units.len();
+                node.value = VersionValue::Text(read_u16_string(
+                    &data[value_start..value_start + chars * 2],
+                )?);
+                node.text_length = match value_length {
+                    _ if value_length == chars + 1 => TextLength::Chars,
+                    _ if value_length == chars => TextLength::CharsWithoutNull,
+                    _ if value_length == chars * 2 + 2 => TextLength::Bytes,
+                    _ if value_length == chars * 2 => TextLength::BytesWithoutNull,
+                    _ => {
+                        trace!(
+                            "keeping version value length {:#x?} of {:?}",
+                            header.value_length, key
+                        );
+                        TextLength::Raw(header.value_length)
+                    }
+                };
+                value_start + chars * 2 + if node.terminated { 2 } else { 0 }
+            }
+            _ => {
+                return Err(ReadError(format!(
+                    "invalid version value type {:#x?} of {:?}",
+                    header.type_, key
+                )));
+            }
+        };
+
+        let mut content_end = value_end;
+        let mut child_offset = aligned(value_end);
+        while child_offset + size_of::<VersionHeader>() <= length {
+            let child_length = read::<VersionHeader>(&data[child_offset..])?.length as usize;
+            if child_length < size_of::<VersionHeader>() || child_offset + child_length > length {
+                break;
+            }
+            let child = Self::parse(&data[child_offset..], offset + child_offset, depth + 1)?;
+            content_end = child_offset + child_length;
+            child_offset = aligned(content_end);
+            node.children.push(child);
+        }
+        let rest = &data[content_end..];
+        if !rest.is_empty() && length == aligned(content_end) && rest.iter().all(|b| *b == 0) {
+            node.padded = true;
+        } else if !rest.is_empty() {
+            trace!("keeping {:#x?} trailing bytes of version node {:?}", rest.len(), key);
+            node.trailing = rest.to_vec();
+        }
+
+        Ok(node)
+    }
+
+    /// Returns if the node is a string entry in a string table.
+    fn is_string(&self) -> bool {
+        matches!(self.value, VersionValue::Text(_)) && self.children.is_empty()
+    }
+
+    /// Set the value of a text node, keeping the layout if the value is unchanged.
+    /// Nodes without a value are given one, so that empty strings are not skipped when parsed again.
+    fn set_text(&mut self, text: &str) {
+        if self.text_length == TextLength::Empty {
+            self.text_length = TextLength::Chars;
+            self.terminated = true;
+        } else if self.value == VersionValue::Text(text.to_string()) {
+            return;
+        } else if matches!(self.text_length, TextLength::Raw(_)) {
+            self.text_length = TextLength::Chars;
+            self.terminated = true;
+        }
+        self.value = VersionValue::Text(text.to_string());
+    }
+
+    /// Returns the string table node with the given strings.
+    /// Existing string entries are updated in place and keep their layout, new strings are appended.
+    /// Nodes that are not string entries and string entries without a value are kept at their position.
+    fn with_strings(mut self, strings: &IndexMap<String, String, RandomState>) -> Self {
+        let previous = core::mem::take(&mut self.children);
+        let string_entry = |key: &String, value: &String| {
+            let mut node = previous
+                .iter()
+                .find(|node| node.key == *key && node.is_string())
+                .cloned()
+                .unwrap_or_else(|| Self::new(key, VersionValue::empty()));
+            node.set_text(value);
+            node
+        };
+
+        let mut seen = IndexSet::<&str, RandomState>::default();
+        let mut entries = strings.iter();
+        for node in &previous {
+            let skipped = node.text_length == TextLength::Empty && !strings.contains_key(&node.key);
+            if !node.is_string() || skipped || !seen.insert(&node.key) {
+                self.children.push(node.clone());
+            } else if let Some((key, value)) = entries.next() {
+                self.children.push(string_entry(key, value));
+            }
+        }
+        for (key, value) in entries {
+            self.children.push(string_entry(key, value));
+        }
+        self
+    }
+
+    /// Build the node and append it to the data.
+    /// The data must start at the beginning of the resource, which is used for the alignment.
+    fn build(&self, data: &mut Vec<u8>) {
+        let align = |data: &mut Vec<u8>| {
+            data.extend(iter::repeat_n(0, aligned_to(data.len(), 4) - data.len()))
+        };
+
+        let start = data.len();
+        data.extend(VersionHeader::default().as_bytes());
+        data.extend(string_to_u16(&self.key));
+        align(data);
+
+        let (type_, value_length) = match &self.value {
+            VersionValue::Binary(value) => {
+                data.extend(value);
+                (0, value.len())
+            }
+            VersionValue::Text(text)
+                if text.is_empty() && self.text_length == TextLength::Empty =>
+            {
+                (1, 0)
+            }
+            VersionValue::Text(text) => {
+                let chars = text.encode_utf16().count();
+                data.extend(text.encode_utf16().flat_map(|c| c.to_le_bytes()));
+                if self.terminated {
+                    data.extend([0, 0]);
+                }
+                let value_length = match self.text_length {
+                    TextLength::Empty | TextLength::Chars => chars + 1,
+                    TextLength::CharsWithoutNull => chars,
+                    TextLength::Bytes => chars * 2 + 2,
+                    TextLength::BytesWithoutNull => chars * 2,
+                    TextLength::Raw(value_length) => value_length as usize,
+                };
+                (1, value_length)
+            }
+        };
+
+        for child in &self.children {
+            align(data);
+            child.build(data);
+        }
+        if self.padded {
+            align(data);
+        }
+        data.extend(&self.trailing);
+
+        let header = VersionHeader {
+            length: (data.len() - start) as u16,
+            value_length: value_length as u16,
+            type_,
+        };
+        data[start..start + size_of::<VersionHeader>()].copy_from_slice(header.as_bytes());
+    }
+}
+
+impl ResourceDirectory {
+    /// Returns the names and languages of all version information resources.
+    /// Localized executables can contain a version information resource for each language.
+    ///
+    /// # Returns
+    /// Returns an error if the resource table structure is not well-formed.
+    pub fn version_infos(&self) -> Result<Vec<(ResourceEntryName, LANGID)>, ResourceError> {
+        self.data_entries(&ResourceEntryName::ID(RT_VERSION as u32), "version")
+    }
+
+    /// Get the version information with the given name and language.
+    ///
+    /// # Returns
+    /// Returns `None` if the version information does not exist.
+    /// Returns an error if the resource table structure or the version information is not well-formed.
+    pub fn get_version_info_resource<N: Borrow<ResourceEntryName>>(
+        &self, name: N, language: LANGID,
+    ) -> Result<Option<VersionInfo>, ResourceError> {
+        let data = self.data_entry(
+            &ResourceEntryName::ID(RT_VERSION as u32),
+            name.borrow(),
+            language,
+            "version",
+        )?;
+        match data {
+            Some(data) => Ok(Some(VersionInfo::parse(data.data())?)),
+            None => Ok(None),
+        }
+    }
+
+    /// Set the version information with the given name and language.
+    ///
+    /// This will overwrite the existing version information with the given name and language and keep all other version information intact.
+    ///
+    /// # Returns
+    /// Returns an error if the resource table structure is not well-formed.
+    pub fn set_version_info_resource<N: Borrow<ResourceEntryName>>(
+        &mut self, name: N, language: LANGID, version_info: &VersionInfo,
+    ) -> Result<(), ResourceError> {
+        self.set_data_entry(
+            &ResourceEntryName::ID(RT_VERSION as u32),
+            name.borrow(),
+            language,
+            version_info.build(),
+            "version",
+        )
+    }
+
+    /// Remove the version information with the given name and language.
+    ///
+    /// # Returns
+    /// Returns an error if the resource table structure is not well-formed.
+    pub fn remove_version_info_resource<N: Borrow<ResourceEntryName>>(
+        &mut self, name: N, language: LANGID,
+    ) -> Result<(), ResourceError> {
+        self.remove_data_entry(
+            &ResourceEntryName::ID(RT_VERSION as u32),
+            name.borrow(),
+            language,
+            "version",
+        )?;
+        Ok(())
+    }
+
+    /// Update every version information resource with the given function.
+    /// The function is called with the parsed version information, its name and its language,
+    /// and the modified version information is written back in place, keeping its layout and unknown content.
+    ///
+    /// # Returns
+    /// Returns the number of updated version information resources.
+    /// Returns an error if the resource table structure or any version information is not well-formed,
+    /// in which case no version information is modified.
+    pub fn update_version_infos<F: FnMut(&mut VersionInfo, &ResourceEntryName, LANGID)>(
+        &mut self, mut update: F,
+    ) -> Result<usize, ResourceError> {
+        let type_ = ResourceEntryName::ID(RT_VERSION as u32);
+        let mut version_infos = Vec::new();
+        for (name, language) in self.version_infos()? {
+            let data = self.data_entry(&type_, &name, language, "version")?.unwrap();
+            let mut version_info = VersionInfo::parse(data.data())?;
+            update(&mut version_info, &name, language);
+            version_infos.push((name, language, version_info.build()));
+        }
+        let count = version_infos.len();
+        for (name, language, data) in version_infos {
+            self.set_data_entry(&type_, &name, language, data, "version")?;
+        }
+        Ok(count)
+    }
+}
+
+/// Builder for a [`VersionInfo`].
+///
+/// Versions are given as strings like `1.2.3.4` or semantic versions like `1.2.3-beta.1+build.5`,
+/// which set both the numeric version in the fixed file info and the version string.
+/// Strings are written to the string table of the selected language, which is also added to the translations.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct VersionInfoBuilder {
+    version_info: VersionInfo,
+    language:     LANGID,
+    code_page:    u16,
+    strings:      IndexMap<String, String, RandomState>,
+}
+impl Default for VersionInfoBuilder {
+    fn default() -> Self {
+        Self {
+            version_info: VersionInfo::default(),
+            language:     LANGUAGE_ID_EN_US,
+            code_page:    CODE_PAGE_ID_EN_US,
+            strings:      IndexMap::default(),
+        }
+    }
+}
+impl From<VersionInfo> for VersionInfoBuilder {
+    /// Create a builder that modifies an existing version info.
+    /// The language and code page are taken from the first translation if present.
+    fn from(version_info: VersionInfo) -> Self {
+        let (language, code_page) = version_info
+            .vars
+            .first()
+            .map(|var| (var.major, var.minor))
+            .unwrap_or((LANGUAGE_ID_EN_US, CODE_PAGE_ID_EN_US));
+        Self {
+            version_info,
+            language,
+            code_page,
+            strings: IndexMap::default(),
+        }
+    }
+}
+impl VersionInfoBuilder {
+    /// Create a builder for a new version info of a Windows application in the `en-US` language.
+    pub fn new() -> Self { Self::default() }
+
+    /// Set the file version in the fixed file info and the `FileVersion` string.
+    /// A semantic version with a pre-release part sets the pre-release flag.
+    ///
+    /// # Returns
+    /// Returns an error if the version is not made of one to four numbers of at most 65535.
+    pub fn file_version(mut self, version: &str) -> Result<Self, ReadError> {
+        let (file_version, prerelease) = parse_version(version)?;
+        self.version_info.info.file_version = file_version;
+        self.strings.insert(VS_FILE_VERSION.to_string(), version.to_string());
+        if prerelease {
+            self = self.prerelease(true);
+        }
+        Ok(self)
+    }
+
+    /// Set the product version in the fixed file info and the `ProductVersion` string.
+    /// A semantic version with a pre-release part sets the pre-release flag.
+    ///
+    /// # Returns
+    /// Returns an error if the version is not made of one to four numbers of at most 65535.
+    pub fn product_version(mut self, version: &str) -> Result<Self, ReadError> {
+        let (product_version, prerelease) = parse_version(version)?;
+        self.version_info.info.product_version = product_version;
+        self.strings.insert(VS_PRODUCT_VERSION.to_string(), version.to_string());
+        if prerelease {
+            self = self.prerelease(true);
+        }
+        Ok(self)
+    }
+
+    /// Set or clear the debug flag.
+    pub fn debug(self, debug: bool) -> Self { self.flag(VS_FF_DEBUG, debug) }
+
+    /// Set or clear the pre-release flag.
+    pub fn prerelease(self, prerelease: bool) -> Self { self.flag(VS_FF_PRERELEASE, prerelease) }
+
+    /// Set or clear the patched flag.
+    pub fn patched(self, patched: bool) -> Self { self.flag(VS_FF_PATCHED, patched) }
+
+    /// Set the `PrivateBuild` string and the private build flag, or clear both with `None`.
+    pub fn private_build(self, description: Option<&str>) -> Self {
+        self.flag(VS_FF_PRIVATEBUILD, description.is_some())
+            .optional_string(VS_PRIVATE_BUILD, description)
+    }
+
+    /// Set the `SpecialBuild` string and the special build flag, or clear both with `None`.
+    pub fn special_build(self, description: Option<&str>) -> Self {
+        self.flag(VS_FF_SPECIALBUILD, description.is_some())
+            .optional_string(VS_SPECIAL_BUILD, description)
+    }
+
+    /// Set the operating system the file was designed for, e.g. [`VOS_NT_WINDOWS32`].
+    pub fn file_os(mut self, file_os: u32) -> Self {
+        self.version_info.info.file_os = file_os;
+        self
+    }
+
+    /// Set the general type of the file, e.g. [`VFT_APP`] or [`VFT_DLL`].
+    pub fn file_type(mut self, file_type: u32) -> Self {
+        self.version_info.info.file_type = file_type;
+        self
+    }
+
+    /// Set the function of the file for drivers and fonts, e.g. [`VFT2_DRV_PRINTER`].
+    pub fn file_subtype(mut self, file_subtype: u32) -> Self {
+        self.version_info.info.file_subtype = file_subtype;
+        self
+    }
+
+    /// Set the language and code page of the string table the strings are written to.
+    pub fn language(mut self, language: LANGID, code_page: u16) -> Self {
+        self.language = language;
+        self.code_page = code_page;
+        self
+    }
+
+    /// Set a string by its key, e.g. [`VS_PRODUCT_NAME`].
+    /// An empty value removes the string.
+    pub fn string(mut self, key: &str, value: &str) -> Self {
+        self.strings.insert(key.to_string(), value.to_string());
+        self
+    }
+
+    /// Build the version info.
+    pub fn build(mut self) -> VersionInfo {
+        let table = self.version_info.add_language(self.language, self.code_page);
+        for (key, value) in self.strings {
+            if value.is_empty() {
+                table.strings.shift_remove(&key);
+            } else {
+                table.strings.insert(key, value);
+            }
+        }
+
+        self.version_info
+    }
+
+    fn flag(mut self, flag: u32, set: bool) -> Self {
+        if set {
+            self.version_info.info.file_flags |= flag;
+        } else {
+            self.version_info.info.file_flags &= !flag;
+        }
+        self
+    }
+
+    fn optional_string(mut self, key: &str, value: Option<&str>) -> Self {
+        self.strings.insert(key.to_string(), value.unwrap_or_default().to_string());
+        self
+    }
+}
+
+/// Inconsistency between the string tables and the translations of a [`VersionInfo`].
+/// This is an entry in the list returned by [`VersionInfo::validate`].
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum VersionInfoIssue {
+    /// The string table key is not made of a hexadecimal language and code page.
+    InvalidKey(String),
+    /// The string table has no matching translation.
+    MissingTranslation(VersionU16),
+    /// The translation has no matching string table.
+    MissingStringTable(VersionU16),
+    /// The translation is listed more than once.
+    DuplicateTranslation(VersionU16),
+}
+
+impl VersionStringTable {
+    /// Get the language and code page of the string table.
+    /// The key contains the language and the code page as hexadecimal numbers, e.g. `040904B0`.
+    ///
+    /// # Returns
+    /// Returns `None` if the key is not well-formed.
+    pub fn translation(&self) -> Option<VersionU16> {
+        if self.key.len() != 8 || !self.key.is_ascii() {
+            return None;
+        }
+        Some(VersionU16 {
+            major: u16::from_str_radix(&self.key[..4], 16).ok()?,
+            minor: u16::from_str_radix(&self.key[4..], 16).ok()?,
+        })
+    }
+}
+
+impl VersionInfo {
+    /// Get the string table with the given language and code page.
+    ///
+    /// # Returns
+    /// Returns `None` if the string table does not exist.
+    pub fn string_table(&self, language: LANGID, code_page: u16) -> Option<&VersionStringTable> {
+        let translation = VersionU16 {
+            major: language,
+            minor: code_page,
+        };
+        self.strings.iter().find(|table| table.translation() == Some(translation))
+    }
+
+    /// Get the mutable string table with the given language and code page.
+    ///
+    /// # Returns
+    /// Returns `None` if the string table does not exist.
+    pub fn string_table_mut(
+        &mut self, language: LANGID, code_page: u16,
+    ) -> Option<&mut VersionStringTable> {
+        let translation = VersionU16 {
+            major: language,
+            minor: code_page,
+        };
+        self.strings.iter_mut().find(|table| table.translation() == Some(translation))
+    }
+
+    /// Add a language with the given code page.
+    /// This adds both the string table and the translation if they do not exist yet.
+    ///
+    /// # Returns
+    /// Returns the string table of the language.
+    pub fn add_language(&mut self, language: LANGID, code_page: u16) -> &mut VersionStringTable {
+        let translation = VersionU16 {
+            major: language,
+            minor: code_page,
+        };
+        if !self.vars.contains(&translation) {
+            self.vars.push(translation);
+        }
+        let index = match self.strings.iter().position(|t| t.translation() == Some(translation)) {
+            Some(index) => index,
+            None => {
+                self.strings.push(VersionStringTable {
+                    key:     format!("{:04X}{:04X}", language, code_page),
+                    strings: IndexMap::default(),
+                });
+                self.strings.len() - 1
+            }
+        };
+        &mut self.strings[index]
+    }
+
+    /// Remove a language with the given code page.
+    /// This removes both the string table and the translation.
+    ///
+    /// # Returns
+    /// Returns the removed string table or `None` if it did not exist.
+    pub fn remove_language(
+        &mut self, language: LANGID, code_page: u16,
+    ) -> Option<VersionStringTable> {
+        let translation = VersionU16 {
+            major: language,
+            minor: code_page,
+        };
+        self.vars.retain(|var| *var != translation);
+        let index = self.strings.iter().position(|t| t.translation() == Some(translation))?;
+        Some(self.strings.remove(index))
+    }
+
+    /// Get a string value by its key, e.g. [`VS_PRODUCT_NAME`], for the given language.
+    /// If the language does not have the value, the string tables are searched in this order:
+    /// the same primary language, the language neutral table, `en-US` and then all other tables.
+    ///
+    /// # Returns
+    /// Returns `None` if no string table contains the value.
+    pub fn get_string(&self, key: &str, language: LANGID) -> Option<&str> {
+        let rank = |table: &VersionStringTable| match table.translation() {
+            Some(translation) if translation.major == language => 0,
+            Some(translation) if translation.major & 0x3ff == language & 0x3ff => 1,
+            Some(translation) if translation.major == 0 => 2,
+            Some(translation) if translation.major == LANGUAGE_ID_EN_US => 3,
+            _ => 4,
+        };
+        let mut tables = self.strings.iter().collect::<Vec<_>>();
+        tables.sort_by_key(|table| rank(table));
+        tables.into_iter().find_map(|table| table.strings.get(key)).map(String::as_str)
+    }
+
+    /// Validate that the string tables and the translations match.
+    /// Windows uses the translations to find the string tables,
+    /// so string tables without a matching translation are not displayed.
+    ///
+    /// # Returns
+    /// Returns the found inconsistencies, which are empty if the version info is consistent.
+    pub fn validate(&self) -> Vec<VersionInfoIssue> {
+        let mut issues = Vec::new();
+        for table in &self.strings {
+            match table.translation() {
+                Some(translation) if !self.vars.contains(&translation) => {
+                    issues.push(VersionInfoIssue::MissingTranslation(translation));
+                }
+                Some(_) => {}
+                None => issues.push(VersionInfoIssue::InvalidKey(table.key.clone())),
+            }
+        }
+        for (index, var) in self.vars.iter().enumerate() {
+            if self.vars[..index].contains(var) {
+                issues.push(VersionInfoIssue::DuplicateTranslation(*var));
+            } else if !self.strings.iter().any(|table| table.translation() == Some(*var)) {
+                issues.push(VersionInfoIssue::MissingStringTable(*var));
+            }
+        }
+        issues
+    }
+
+    /// Repair the inconsistencies reported by [`validate`](Self::validate).
+    /// Translations without a string table and duplicate translations are removed,
+    /// and string tables without a translation get one appended.
+    /// String tables with invalid keys are kept as they are.
+    pub fn repair(&mut self) {
+        let mut vars = Vec::new();
+        for var in &self.vars {
+            if !vars.contains(var)
+                && self.strings.iter().any(|table| table.translation() == Some(*var))
+            {
+                vars.push(*var);
+            }
+        }
+        for translation in self.strings.iter().filter_map(VersionStringTable::translation) {
+            if !vars.contains(&translation) {
+                vars.push(translation);
+            }
+        }
+        self.vars = vars;
+    }
+}
+
+/// Parse a version string into the packed fixed file info version.
+/// Returns if the version has a semantic version pre-release part.
+fn parse_version(version: &str) -> Result<(VersionU32, bool), ReadError> {
+    let version_core = version.trim().trim_start_matches(['v', 'V']);
+    let version_core = version_core.split('+').next().unwrap_or_default();
+    let (version_core, prerelease) = match version_core.split_once('-') {
+        Some((version_core, prerelease)) => (version_core, !prerelease.is_empty()),
+        None => (version_core, false),
+    };
+
+    let mut parts = [0u16; 4];
+    for (index, part) in version_core.split('.').enumerate() {
+        if index == parts.len() {
+            return Err(ReadError(format!("version {:?} has more than four parts", version)));
+        }
+        parts[index] = part
+            .parse()
+            .map_err(|_| ReadError(format!("invalid version part {:?} in {:?}", part, version)))?;
+    }
+
+    Ok((
+        VersionU32 {
+            major: ((parts[0] as u32) << 16) | parts[1] as u32,
+            minor: ((parts[2] as u32) << 16) | parts[3] as u32,
+        },
+        prerelease,
+    ))
+}
@@ -0,0 +1,434 @@
+//! Minimal XML document tree for editing manifests.
+//!
+//! The tree keeps the original formatting of all nodes, so a document that is parsed and written without changes is identical to the input.
+//! Namespaces are not resolved and elements are matched by their local name.
+
+use alloc::{
+    format,
+    string::{String, ToString},
+    vec,
+    vec::Vec,
+};
+
+use crate::errors::*;
+
+const MAX_DEPTH: usize = 256;
+
+/// XML node.
+/// Comments, processing instructions, CDATA sections and declarations are kept as raw markup.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub(crate) enum XmlNode {
+    Element(XmlElement),
+    Text(String),
+    Other(String),
+}
+
+/// XML attribute with its original formatting.
+#[derive(Debug, Clone, Eq, PartialEq)]
+struct XmlAttribute {
+    space:  String,
+    name:   String,
+    equals: String,
+    quote:  char,
+    value:  String,
+}
+
+/// XML element with its original formatting.
+/// Attribute values and text are stored escaped.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub(crate) struct XmlElement {
+    pub(crate) name:     String,
+    attributes:          Vec<XmlAttribute>,
+    tail:                String,
+    end_space:           String,
+    self_closing:        bool,
+    pub(crate) children: Vec<XmlNode>,
+}
+impl XmlElement {
+    /// Create an empty element.
+    pub(crate) fn new(name: &str) -> Self {
+        Self {
+            name:         name.to_string(),
+            attributes:   Vec::new(),
+            tail:         String::new(),
+            end_space:    String::new(),
+            self_closing: true,
+            children:     Vec::new(),
+        }
+    }
+
+    /// Create an empty element with a default namespace.
+    pub(crate) fn with_namespace(name: &str, namespace: &str) -> Self {
+        let mut element = Self::new(name);
+        element.set_attribute("xmlns", namespace);
+        element
+    }
+
+    /// Create an empty element with the namespace prefix of this element, so it can be added as a child in the same namespace.
+    pub(crate) fn new_child(&self, name: &str) -> Self {
+        let prefix = &self.name[..self.name.len() - self.local_name().len()];
+        Self::new(&format!("{}{}", prefix, name))
+    }
+
+    /// Returns the name of the element without the namespace prefix.
+    pub(crate) fn local_name(&self) -> &str { local_name(&self.name) }
+
+    /// Returns the unescaped value of an attribute.
+    pub(crate) fn attribute(&self, name: &str) -> Option<String> {
+        self.attributes.iter().find(|a| a.name == name).map(|a| unescape(&a.value))
+    }
+
+    /// Set the value of an attribute, keeping the position and formatting of an existing attribute.
+    pub(crate) fn set_attribute(&mut self, name: &str, value: &str) {
+        match self.attributes.iter_mut().find(|a| a.name == name) {
+            Some(attribute) => attribute.value = escape(value, attribute.quote),
+            None => self.attributes.push(XmlAttribute {
+                space:  " ".to_string(),
+                name:   name.to_string(),
+                equals: "=".to_string(),
+                quote:  '"',
+                value:  escape(value, '"'),
+            }),
+        }
+    }
+
+    /// Remove an attribute.
+    pub(crate) fn remove_attribute(&mut self, name: &str) {
+        self.attributes.retain(|a| a.name != name);
+    }
+
+    /// Returns the child elements.
+    pub(crate) fn elements(&self) -> impl Iterator<Item = &XmlElement> {
+        self.children.iter().filter_map(|node| match node {
+            XmlNode::Element(element) => Some(element),
+            _ => None,
+        })
+    }
+
+    /// Returns the first child element with the given local name.
+    pub(crate) fn child(&self, name: &str) -> Option<&XmlElement> {
+        self.elements().find(|element| element.local_name() == name)
+    }
+
+    /// Returns the first mutable child element with the given local name.
+    pub(crate) fn child_mut(&mut self, name: &str) -> Option<&mut XmlElement> {
+        self.children.iter_mut().find_map(|node| match node {
+            XmlNode::Element(element) if element.local_name() == name => Some(element),
+            _ => None,
+        })
+    }
+
+    /// Returns the unescaped text content of the element.
+    pub(crate) fn text(&self) -> String {
+        self.children
+            .iter()
+            .filter_map(|node| match node {
+                XmlNode::Text(text) => Some(unescape(text)),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Replace the content of the element with text.
+    pub(crate) fn set_text(&mut self, text: &str) {
+        self.children = vec![XmlNode::Text(escape(text, '"'))];
+        self.self_closing = false;
+    }
+
+    /// Append a child element after the last child element.
+    /// The indentation of the existing child elements is used, or derived from the `depth` of the child otherwise.
+    pub(crate) fn append_child(&mut self, element: XmlElement, depth: usize) {
+        let last = self.children.iter().rposition(|node| matches!(node, XmlNode::Element(_)));
+        match last {
+            Some(last) => {
+                let indent = match last.checked_sub(1).map(|i| &self.children[i]) {
+                    Some(XmlNode::Text(text)) if text.trim().is_empty() => text.clone(),
+                    _ => indent(depth),
+                };
+                self.children.insert(last + 1, XmlNode::Element(element));
+                self.children.insert(last + 1, XmlNode::Text(indent));
+            }
+            None => {
+                let only_space = self.children.iter().all(|node| match node {
+                    XmlNode::Text(text) => text.trim().is_empty(),
+                    _ => false,
+                });
+                if only_space {
+                    self.children.clear();
+                }
+                self.children.push(XmlNode::Text(indent(depth)));
+                self.children.push(XmlNode::Element(element));
+                if only_space {
+                    self.children.push(XmlNode::Text(indent(depth.saturating_sub(1))));
+                }
+            }
+        }
+        self.self_closing = false;
+    }
+
+    /// Insert a child element before the first child element.
+    /// The indentation of the existing child elements is used, or derived from the `depth` of the child otherwise.
+    pub(crate) fn prepend_child(&mut self, element: XmlElement, depth: usize) {
+        match self.children.iter().position(|node| matches!(node, XmlNode::Element(_))) {
+            Some(first) => {
+                let indent = match first.checked_sub(1).map(|i| &self.children[i]) {
+                    Some(XmlNode::Text(text)) if text.trim().is_empty() => text.clone(),
+                    _ => indent(depth),
+                };
+                self.children.insert(first, XmlNode::Text(indent));
+                self.children.insert(first, XmlNode::Element(element));
+            }
+            None => self.append_child(element, depth),
+        }
+    }
+
+    /// Remove all child elements matching the predicate, including the whitespace preceding them.
+    pub(crate) fn remove_children<F: Fn(&XmlElement) -> bool>(&mut self, predicate: F) {
+        let mut removed = false;
+        let mut i = 0;
+        while i < self.children.len() {
+            match &self.children[i] {
+                XmlNode::Element(element) if predicate(element) => {
+                    removed = true;
+                    self.children.remove(i);
+                    if i > 0
+                        && matches!(&self.children[i - 1], XmlNode::Text(text) if text.trim().is_empty())
+                    {
+                        self.children.remove(i - 1);
+                        i -= 1;
+                    }
+                }
+                _ => i += 1,
+            }
+        }
+        if removed
+            && self.elements().next().is_none()
+            && self
+                .children
+                .iter()
+                .all(|node| matches!(node, XmlNode::Text(text) if text.trim().is_empty()))
+        {
+            self.children.clear();
+            self.self_closing = true;
+        }
+    }
+
+    fn write(&self, output: &mut String) {
+        output.push('<');
+        output.push_str(&self.name);
+        for attribute in &self.attributes {
+            output.push_str(&attribute.space);
+            output.push_str(&attribute.name);
+            output.push_str(&attribute.equals);
+            output.push(attribute.quote);
+            output.push_str(&attribute.value);
+            output.push(attribute.quote);
+        }
+        output.push_str(&self.tail);
+        if self.self_closing && self.children.is_empty() {
+            output.push_str("/>");
+            return;
+        }
+        output.push('>');
+        write(&self.children, output);
+        output.push_str("</");
+        output.push_str(&self.name);
+        output.push_str(&self.end_space);
+        output.push('>');
+    }
+}
+
+/// Returns the name without the namespace prefix.
+pub(crate) fn local_name(name: &str) -> &str { name.rsplit(':').next().unwrap_or(name) }
+
+fn indent(depth: usize) -> String {
+    let mut indent = String::from("\n");
+    for _ in 0..depth {
+        indent.push_str("  ");
+    }
+    indent
+}
+
+/// Parse an XML document into its top-level nodes.
+///
+/// # Returns
+/// Returns an error if the document is not well-formed.
+pub(crate) fn parse(document: &str) -> Result<Vec<XmlNode>, ReadError> {
+    let mut nodes = Vec::new();
+    let mut stack: Vec<XmlElement> = Vec::new();
+    let mut rest = document;
+
+    fn push(stack: &mut [XmlElement], nodes: &mut Vec<XmlNode>, node: XmlNode) {
+        match stack.last_mut() {
+            Some(parent) => parent.children.push(node),
+            None => nodes.push(node),
+        }
+    }
+    fn read_markup<'a>(rest: &mut &'a str, end: &str) -> Result<&'a str, ReadError> {
+        let length = rest.find(end).ok_or("xml markup is not terminated")? + end.len();
+        let markup = &rest[..length];
+        *rest = &rest[length..];
+        Ok(markup)
+    }
+    fn read_name<'a>(rest: &mut &'a str) -> &'a str {
+        let length = rest
+            .find(|c: char| c.is_whitespace() || c == '/' || c == '>' || c == '=')
+            .unwrap_or(rest.len());
+        let name = &rest[..length];
+        *rest = &rest[length..];
+        name
+    }
+    fn read_space<'a>(rest: &mut &'a str) -> &'a str {
+        let length = rest.find(|c: char| !c.is_whitespace()).unwrap_or(rest.len());
+        let space = &rest[..length];
+        *rest = &rest[length..];
+        space
+    }
+
+    while !rest.is_empty() {
+        if rest.starts_with("<!--") {
+            let node = XmlNode::Other(read_markup(&mut rest, "-->")?.to_string());
+            push(&mut stack, &mut nodes, node);
+        } else if rest.starts_with("<![CDATA[") {
+            let node = XmlNode::Other(read_markup(&mut rest, "]]>")?.to_string());
+            push(&mut stack, &mut nodes, node);
+        } else if rest.starts_with("<?") {
+            let node = XmlNode::Other(read_markup(&mut rest, "?>")?.to_string());
+            push(&mut stack, &mut nodes, node);
+        } else if rest.starts_with("<!") {
+            let node = XmlNode::Other(read_markup(&mut rest, ">")?.to_string());
+            push(&mut stack, &mut nodes, node);
+        } else if let Some(tag) = rest.strip_prefix("</") {
+            rest = tag;
+            let name = read_name(&mut rest);
+            let end_space = read_space(&mut rest);
+            rest = rest.strip_prefix('>').ok_or("xml end tag is not terminated")?;
+            let mut element = stack.pop().ok_or("xml end tag has no start tag")?;
+            if element.name != name {
+                return Err(ReadError(format!(
+                    "xml end tag {} does not match start tag {}",
+                    name, element.name
+                )));
+            }
+            element.end_space = end_space.to_string();
+            push(&mut stack, &mut nodes, XmlNode::Element(element));
+        } else if let Some(tag) = rest.strip_prefix('<') {
+            rest = tag;
+            let mut element = XmlElement::new(read_name(&mut rest));
+            if element.name.is_empty() {
+                return Err("xml start tag has no name".into());
+            }
+            loop {
+                let space = read_space(&mut rest);
+                if let Some(tag) = rest.strip_prefix("/>") {
+                    rest = tag;
+                    element.tail = space.to_string();
+                    push(&mut stack, &mut nodes, XmlNode::Element(element));
+                    break;
+                }
+                if let Some(tag) = rest.strip_prefix('>') {
+                    rest = tag;
+                    element.tail = space.to_string();
+                    element.self_closing = false;
+                    if stack.len() >= MAX_DEPTH {
+                        return Err("xml document is nested too deeply".into());
+                    }
+                    stack.push(element);
+                    break;
+                }
+                let name = read_name(&mut rest);
+                let equals_start = rest;
+                read_space(&mut rest);
+                rest = rest.strip_prefix('=').ok_or("xml attribute has no value")?;
+                read_space(&mut rest);
+                let equals = &equals_start[..equals_start.len() - rest.len()];
+                let quote = rest.chars().next().filter(|&c| c == '"' || c == '\'');
+                let quote = quote.ok_or("xml attribute value is not quoted")?;
+                rest = &rest[1..];
+                let length = rest.find(quote).ok_or("xml attribute value is not terminated")?;
+                if name.is_empty() || space.is_empty() {
+                    return Err("xml attribute is malformed".into());
+                }
+                element.attributes.push(XmlAttribute {
+                    space: space.to_string(),
+                    name: name.to_string(),
+                    equals: equals.to_string(),
+                    quote,
+                    value: rest[..length].to_string(),
+                });
+                rest = &rest[length + 1..];
+            }
+        } else {
+            let length = rest.find('<').unwrap_or(rest.len());
+            push(&mut stack, &mut nodes, XmlNode::Text(rest[..length].to_string()));
+            rest = &rest[length..];
+        }
+    }
+    if let Some(element) = stack.last() {
+        return Err(ReadError(format!("xml element {} is not closed", element.name)));
+    }
+    Ok(nodes)
+}
+
+/// Write XML nodes with their original formatting.
+pub(crate) fn write(nodes: &[XmlNode], output: &mut String) {
+    for node in nodes {
+        match node {
+            XmlNode::Element(element) => element.write(output),
+            XmlNode::Text(text) | XmlNode::Other(text) => output.push_str(text),
+        }
+    }
+}
+
+fn escape(value: &str, quote: char) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' if quote == '"' => escaped.push_str("&quot;"),
+            '\'' if quote == '\'' => escaped.push_str("&apos;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+fn unescape(value: &str) -> String {
+    let mut unescaped = String::with_capacity(value.len());
+    let mut rest = value;
+    while let Some(start) = rest.find('&') {
+        unescaped.push_str(&rest[..start]);
+        rest = &rest[start..];
+        let end = match rest.find(';') {
+            Some(end) => end,
+            None => break,
+        };
+        let c = match &rest[1..end] {
+            "amp" => Some('&'),
+            "lt" => Some('<'),
+            "gt" => Some('>'),
+            "quot" => Some('"'),
+            "apos" => Some('\''),
+            entity => entity
+                .strip_prefix("#x")
+                .map(|hex| u32::from_str_radix(hex, 16))
+                .or_else(|| entity.strip_prefix('#').map(|dec| dec.parse::<u32>()))
+                .and_then(|c| c.ok())
+                .and_then(char::from_u32),
+        };
+        match c {
+            Some(c) => {
+                unescaped.push(c);
+                rest = &rest[end + 1..];
+            }
+            None => {
+                unescaped.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    unescaped.push_str(rest);
+    unescaped
+}
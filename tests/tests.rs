@@ -1,4 +1,4 @@
-use editpe::{constants::*, *};
+use editpe::{constants::*, types::*, *};
 use std::sync::Once;
 
 static BINARY_PATH_SMALL: &str = "./tests/assets/smallbin.exe";
@@ -531,6 +531,542 @@ fn set_version_info() {
     );
 }
 
+#[test]
+fn build_version_info_builder() {
+    init_logger();
+
+    let version_info = VersionInfoBuilder::new()
+        .file_version("1.2.3-beta.1+build.5")
+        .unwrap()
+        .product_version("v4.5")
+        .unwrap()
+        .debug(true)
+        .special_build(Some("nightly"))
+        .file_type(VFT_DLL)
+        .string(VS_PRODUCT_NAME, "Damocles")
+        .build();
+    let info = version_info.info;
+    assert_eq!({ info.file_version }, VersionU32 {
+        major: 0x0001_0002,
+        minor: 0x0003_0000,
+    });
+    assert_eq!({ info.product_version }, VersionU32 {
+        major: 0x0004_0005,
+        minor: 0,
+    });
+    assert_eq!(info.file_flags, VS_FF_DEBUG | VS_FF_PRERELEASE | VS_FF_SPECIALBUILD);
+    assert_eq!(info.file_flags_mask, VS_FFI_FILEFLAGSMASK);
+    assert_eq!((info.file_os, info.file_type), (VOS_NT_WINDOWS32, VFT_DLL));
+    assert_eq!(version_info.strings.len(), 1);
+    assert_eq!(version_info.strings[0].key, "040904B0");
+    assert_eq!(version_info.strings[0].strings[VS_FILE_VERSION], "1.2.3-beta.1+build.5");
+    assert_eq!(version_info.strings[0].strings[VS_PRODUCT_VERSION], "v4.5");
+    assert_eq!(version_info.strings[0].strings[VS_SPECIAL_BUILD], "nightly");
+    assert_eq!(version_info.strings[0].strings[VS_PRODUCT_NAME], "Damocles");
+    assert_eq!(version_info.vars, vec![VersionU16 {
+        major: LANGUAGE_ID_EN_US,
+        minor: 1200,
+    }]);
+    assert_eq!(VersionInfo::parse(&version_info.build()).unwrap(), version_info);
+
+    let data_large = std::fs::read(BINARY_PATH_LARGE).unwrap();
+    let image_large = Image::parse(&data_large[..]).unwrap();
+    let original = image_large.resource_directory().unwrap().get_version_info().unwrap().unwrap();
+    let version_info = VersionInfoBuilder::from(original.clone())
+        .file_version("2.0.0.7")
+        .unwrap()
+        .special_build(None)
+        .build();
+    assert_eq!({ version_info.info.file_version }, VersionU32 {
+        major: 0x0002_0000,
+        minor: 7,
+    });
+    assert_eq!(version_info.strings.len(), original.strings.len());
+    assert_eq!(version_info.vars, original.vars);
+    assert_eq!(version_info.strings[0].strings[VS_FILE_VERSION], "2.0.0.7");
+    assert!(!version_info.strings[0].strings.contains_key(VS_SPECIAL_BUILD));
+
+    assert!(VersionInfoBuilder::new().file_version("1.2.3.4.5").is_err());
+    assert!(VersionInfoBuilder::new().file_version("1.65536").is_err());
+    assert!(VersionInfoBuilder::new().product_version("one").is_err());
+}
+
+#[test]
+fn version_info_languages() {
+    init_logger();
+
+    let data_large = std::fs::read(BINARY_PATH_LARGE).unwrap();
+    let image_large = Image::parse(&data_large[..]).unwrap();
+    let mut version_info =
+        image_large.resource_directory().unwrap().get_version_info().unwrap().unwrap();
+    assert!(version_info.validate().is_empty(), "original version info is consistent");
+    assert_eq!(version_info.get_string(VS_PRODUCT_NAME, 1031), Some("smallbin"));
+
+    let german = version_info.add_language(1031, 1200);
+    assert_eq!(german.key, "040704B0");
+    german.strings.insert(VS_PRODUCT_NAME.to_string(), "Kleinbin".to_string());
+    version_info.add_language(2055, 1200);
+    assert!(version_info.validate().is_empty(), "added languages are consistent");
+    assert_eq!(version_info.get_string(VS_PRODUCT_NAME, 1031), Some("Kleinbin"));
+    assert_eq!(version_info.get_string(VS_PRODUCT_NAME, 2055), Some("Kleinbin"));
+    assert_eq!(version_info.get_string(VS_PRODUCT_NAME, 1036), Some("smallbin"));
+    assert_eq!(version_info.get_string(VS_COMMENTS, 1031), None);
+
+    let swiss = VersionU16 {
+        major: 2055,
+        minor: 1200,
+    };
+    let french = VersionU16 {
+        major: 1036,
+        minor: 1252,
+    };
+    version_info.vars.retain(|var| *var != swiss);
+    version_info.vars.push(french);
+    version_info.vars.push(french);
+    version_info.strings.push(VersionStringTable {
+        key: "invalid".to_string(),
+        ..Default::default()
+    });
+    assert_eq!(version_info.validate(), vec![
+        VersionInfoIssue::MissingTranslation(swiss),
+        VersionInfoIssue::InvalidKey("invalid".to_string()),
+        VersionInfoIssue::MissingStringTable(french),
+        VersionInfoIssue::DuplicateTranslation(french),
+    ]);
+    version_info.repair();
+    assert_eq!(version_info.validate(), vec![VersionInfoIssue::InvalidKey(
+        "invalid".to_string()
+    )]);
+    assert_eq!(version_info.vars, vec![
+        VersionU16 {
+            major: 0,
+            minor: 1200,
+        },
+        VersionU16 {
+            major: 1031,
+            minor: 1200,
+        },
+        swiss
+    ]);
+
+    let removed = version_info.remove_language(1031, 1200).unwrap();
+    assert_eq!(removed.strings[VS_PRODUCT_NAME], "Kleinbin");
+    assert!(version_info.string_table(1031, 1200).is_none());
+    assert!(version_info.string_table(0, 1200).is_some());
+    assert_eq!(version_info.vars.len(), 2);
+}
+
+#[test]
+fn version_info_round_trip() {
+    init_logger();
+
+    for path in [BINARY_PATH_SMALL, BINARY_PATH_LARGE, BINARY_PATH_UPX] {
+        let data = std::fs::read(path).unwrap();
+        let image = Image::parse(&data[..]).unwrap();
+        let version_table =
+            image.resource_directory().unwrap().root().get(ResourceEntryName::ID(16));
+        let version_table = version_table.unwrap().as_table().unwrap();
+        for name in version_table.entries() {
+            let inner_table = version_table.get(name.clone()).unwrap().as_table().unwrap();
+            for language in inner_table.entries() {
+                let data = inner_table.get(language.clone()).unwrap().as_data().unwrap().data();
+                let version_info = VersionInfo::parse(data).unwrap();
+                assert_eq!(version_info.build(), data, "{} version info round-trips", path);
+            }
+        }
+    }
+
+    // version node with a header, a key, a value and children aligned to 32-bit boundaries
+    fn node(
+        key: &str, type_: u16, value: &[u8], value_length: u16, children: &[Vec<u8>],
+    ) -> Vec<u8> {
+        let mut data = vec![0; 6];
+        data.extend(key.encode_utf16().chain([0]).flat_map(u16::to_le_bytes));
+        data.resize(data.len().next_multiple_of(4), 0);
+        data.extend(value);
+        for child in children {
+            data.resize(data.len().next_multiple_of(4), 0);
+            data.extend(child);
+        }
+        let length = data.len() as u16;
+        data[0..2].copy_from_slice(&length.to_le_bytes());
+        data[2..4].copy_from_slice(&value_length.to_le_bytes());
+        data[4..6].copy_from_slice(&type_.to_le_bytes());
+        data
+    }
+    let text =
+        |text: &str| text.encode_utf16().chain([0]).flat_map(u16::to_le_bytes).collect::<Vec<_>>();
+    let mut padded_table = node("040704B0", 1, &[], 0, &[
+        node("ProductName", 1, &text("abc"), 8, &[]),
+        node("Checksum", 0, &[1, 2, 3, 4, 5], 5, &[]),
+        node("Comments", 1, &text("x"), 1, &[]),
+        node("Empty", 1, &[], 0, &[]),
+        node("Odd", 1, &text("ab"), 5, &[]),
+    ]);
+    padded_table.resize(padded_table.len().next_multiple_of(4), 0);
+    let length = padded_table.len() as u16;
+    padded_table[0..2].copy_from_slice(&length.to_le_bytes());
+    let mut trailing_node = node("Data", 0, &[9; 3], 3, &[]);
+    trailing_node.extend([0, 0xAB, 0xCD, 0, 0, 0, 0, 1, 2]);
+    let length = trailing_node.len() as u16;
+    trailing_node[0..2].copy_from_slice(&length.to_le_bytes());
+    let data = node("VS_VERSION_INFO", 0, &VersionInfo::default().build()[40..92], 52, &[
+        node("StringFileInfo", 1, &[], 0, &[padded_table]),
+        node("CustomFileInfo", 1, &[], 0, &[trailing_node]),
+        node("VarFileInfo", 1, &[], 0, &[node("Translation", 0, &[7, 4, 0xb0, 4], 4, &[])]),
+    ]);
+
+    let mut version_info = VersionInfo::parse(&data).unwrap();
+    assert_eq!(version_info.build(), data, "unknown nodes and layouts round-trip");
+    let strings = &version_info.strings[0].strings;
+    assert_eq!(strings.keys().collect::<Vec<_>>(), vec!["ProductName", "Comments", "Odd"]);
+    assert_eq!(strings["ProductName"], "abc");
+    assert_eq!(strings["Odd"], "ab");
+    assert_eq!(version_info.vars, vec![VersionU16 {
+        major: 1031,
+        minor: 1200,
+    }]);
+
+    version_info.strings[0]
+        .strings
+        .insert("Comments".to_string(), "xyz".to_string());
+    version_info.strings[0]
+        .strings
+        .insert("FileDescription".to_string(), "d".to_string());
+    let rebuilt = version_info.build();
+    assert_eq!(
+        VersionInfo::parse(&rebuilt).unwrap(),
+        version_info,
+        "modified version info is rebuilt"
+    );
+    assert!(
+        rebuilt.windows(6).any(|w| w == [1, 2, 3, 4, 5, 0]),
+        "binary string value is kept"
+    );
+    assert!(rebuilt.windows(3).any(|w| w == [9, 9, 9]), "unknown node is kept");
+    assert!(rebuilt.windows(4).any(|w| w == [0xAB, 0xCD, 0, 0]), "trailing data is kept");
+    let empty_key = "Empty".encode_utf16().flat_map(u16::to_le_bytes).collect::<Vec<_>>();
+    assert!(
+        rebuilt.windows(10).any(|w| w == empty_key),
+        "string entry without value is kept"
+    );
+
+    version_info.strings[0].strings.insert("Empty".to_string(), String::new());
+    let rebuilt = VersionInfo::parse(&version_info.build()).unwrap();
+    assert_eq!(rebuilt.strings[0].strings["Empty"], "", "empty string is set");
+    assert_eq!(rebuilt, version_info);
+    assert_eq!(
+        VersionInfo::default(),
+        VersionInfo::parse(&VersionInfo::default().build()).unwrap()
+    );
+}
+
+#[test]
+fn set_version_info_resource() {
+    init_logger();
+
+    let data_large = std::fs::read(BINARY_PATH_LARGE).unwrap();
+    let mut image_large = Image::parse(&data_large[..]).unwrap();
+    let mut resource_directory = image_large.resource_directory().cloned().unwrap();
+    let id = ResourceEntryName::ID(1);
+    assert_eq!(resource_directory.version_infos().unwrap(), vec![(
+        id.clone(),
+        LANGUAGE_ID_EN_US
+    )]);
+
+    let original = resource_directory.get_version_info().unwrap().unwrap();
+    let mut german = original.clone();
+    german
+        .add_language(1031, 1200)
+        .strings
+        .insert(VS_PRODUCT_NAME.to_string(), "Kleinbin".to_string());
+    resource_directory.set_version_info_resource(&id, 1031, &german).unwrap();
+    assert_eq!(resource_directory.version_infos().unwrap(), vec![
+        (id.clone(), LANGUAGE_ID_EN_US),
+        (id.clone(), 1031)
+    ]);
+    assert_eq!(resource_directory.get_version_info_resource(&id, 1031).unwrap(), Some(german));
+    assert_eq!(resource_directory.get_version_info().unwrap(), Some(original));
+    assert_eq!(resource_directory.get_version_info_resource(&id, 1036).unwrap(), None);
+
+    let mut languages = Vec::new();
+    let updated = resource_directory
+        .update_version_infos(|version_info, _, language| {
+            languages.push(language);
+            *version_info = VersionInfoBuilder::from(version_info.clone())
+                .file_version("2.0.0")
+                .unwrap()
+                .build();
+        })
+        .unwrap();
+    assert_eq!(updated, 2);
+    assert_eq!(languages, vec![LANGUAGE_ID_EN_US, 1031]);
+
+    image_large.set_resource_directory(resource_directory).unwrap();
+    let data_rebuilt = image_large.data().to_vec();
+    let image_rebuilt = Image::parse(&data_rebuilt[..]).unwrap();
+    let mut resource_directory = image_rebuilt.resource_directory().cloned().unwrap();
+    for language in [LANGUAGE_ID_EN_US, 1031] {
+        let version_info =
+            resource_directory.get_version_info_resource(&id, language).unwrap().unwrap();
+        assert_eq!(version_info.strings[0].strings[VS_FILE_VERSION], "2.0.0");
+        assert_eq!({ version_info.info.file_version }, VersionU32 {
+            major: 0x0002_0000,
+            minor: 0,
+        });
+    }
+    let german = resource_directory.get_version_info_resource(&id, 1031).unwrap().unwrap();
+    assert_eq!(german.get_string(VS_PRODUCT_NAME, 1031), Some("Kleinbin"));
+
+    resource_directory.remove_version_info_resource(&id, LANGUAGE_ID_EN_US).unwrap();
+    assert_eq!(resource_directory.version_infos().unwrap(), vec![(id, 1031)]);
+}
+
+#[test]
+fn unicode_resource_names() {
+    init_logger();
+
+    let names = [
+        ResourceEntryName::from_string("图标资源"),
+        ResourceEntryName::from_string("🦀 crab"),
+        ResourceEntryName::from_utf16(&[0xD83E, 0x41, 0xDD80]).unwrap(),
+    ];
+    assert_eq!(names[0].to_utf16().unwrap().len(), 4);
+    assert_eq!(names[1].to_utf16().unwrap().len(), 7);
+    assert_eq!(names[1].to_string().as_deref(), Some("🦀 crab"));
+    assert_eq!(names[2].to_string().as_deref(), Some("\u{FFFD}A\u{FFFD}"));
+    assert_eq!(ResourceEntryName::ID(1).to_utf16(), None);
+    assert!(ResourceEntryName::from_utf16(&vec![0x41; u16::MAX as usize + 1]).is_err());
+    assert!("A".repeat(u16::MAX as usize + 1).parse::<ResourceEntryName>().is_err());
+
+    // strings with unpaired surrogates are rejected instead of being replaced
+    let menu_data = [&[0u8, 0, 0, 0, 0x80, 0, 1, 0][..], &[0x41, 0x00, 0, 0]].concat();
+    assert_eq!(Menu::parse(&menu_data).unwrap().items[0].text, "A");
+    let menu_data = [&[0u8, 0, 0, 0, 0x80, 0, 1, 0][..], &[0x00, 0xD8, 0, 0]].concat();
+    assert!(Menu::parse(&menu_data).is_err());
+
+    let data_large = std::fs::read(BINARY_PATH_LARGE).unwrap();
+    let mut image_large = Image::parse(&data_large[..]).unwrap();
+    let mut resource_directory = image_large.resource_directory().cloned().unwrap();
+    for name in &names {
+        resource_directory
+            .set_manifest_resource(name, LANGUAGE_ID_EN_US, "<assembly/>")
+            .unwrap();
+    }
+    image_large.set_resource_directory(resource_directory).unwrap();
+    let data_rebuilt = image_large.data().to_vec();
+    let image_rebuilt = Image::parse(&data_rebuilt[..]).unwrap();
+    let resource_directory = image_rebuilt.resource_directory().unwrap();
+
+    let manifests = resource_directory.manifests().unwrap();
+    for name in &names {
+        assert!(
+            manifests.contains(&(name.clone(), LANGUAGE_ID_EN_US)),
+            "{:?} round-trips",
+            name.to_string()
+        );
+        assert_eq!(
+            resource_directory
+                .get_manifest_resource(name, LANGUAGE_ID_EN_US)
+                .unwrap()
+                .as_deref(),
+            Some("<assembly/>")
+        );
+    }
+    assert_eq!(manifests.iter().filter(|(name, _)| names.contains(name)).count(), names.len());
+
+    let version_info = VersionInfoBuilder::new().string(VS_PRODUCT_NAME, "🦀 蟹").build();
+    let version_info_rebuilt = VersionInfo::parse(&version_info.build()).unwrap();
+    assert_eq!(version_info_rebuilt.strings[0].strings[VS_PRODUCT_NAME], "🦀 蟹");
+}
+
+#[test]
+fn resource_type_and_names() {
+    init_logger();
+
+    let data_large = std::fs::read(BINARY_PATH_LARGE).unwrap();
+    let image_large = Image::parse(&data_large[..]).unwrap();
+    let mut resource_directory = image_large.resource_directory().cloned().unwrap();
+    let root = resource_directory.root_mut();
+    assert!(root.get(ResourceType::Version).is_some());
+    assert_eq!(root.get(ResourceType::Manifest), root.get(RT_MANIFEST));
+    assert_eq!(
+        root.get(ResourceType::GroupIcon).unwrap().as_table().unwrap().get("MAINICON"),
+        root.get(ResourceEntryName::ID(RT_GROUP_ICON as u32))
+            .unwrap()
+            .as_table()
+            .unwrap()
+            .get(ResourceEntryName::from_string("MAINICON"))
+    );
+    root.insert(
+        ResourceType::Custom("CONFIG".into()),
+        ResourceEntry::Table(ResourceTable::default()),
+    );
+    assert!(root.get("CONFIG").is_some());
+    let name = ResourceEntryName::from_string("CONFIG");
+    assert!(root.get_mut(&name).is_some());
+    assert!(root.remove(ResourceType::Custom("CONFIG".into())).is_some());
+    assert!(root.get("CONFIG").is_none());
+
+    for type_ in [
+        ResourceType::Icon,
+        ResourceType::Manifest,
+        ResourceType::Custom(300.into()),
+    ] {
+        assert_eq!(ResourceType::from(ResourceEntryName::from(type_.clone())), type_);
+    }
+    assert_eq!(ResourceType::from(ResourceEntryName::from(RT_HTML)), ResourceType::HTML);
+    assert_eq!(ResourceType::Manifest.to_string(), "RT_MANIFEST");
+    assert_eq!(ResourceType::Custom("CONFIG".into()).to_string(), "CONFIG");
+    assert_eq!(ResourceType::Custom("CONFIG".into()).id(), None);
+    assert_eq!(ResourceType::Custom(ResourceEntryName::ID(RT_ICON as u32)), ResourceType::Icon);
+    let paths = std::collections::HashSet::from([
+        ResourcePath::new(ResourceType::Custom(RT_ICON.into()), 1, LANGUAGE_ID_EN_US),
+        ResourcePath::new(ResourceType::Icon, 1, LANGUAGE_ID_EN_US),
+    ]);
+    assert_eq!(paths.len(), 1, "standard IDs in custom types are the same type");
+
+    assert_eq!("#123".parse::<ResourceEntryName>().unwrap(), ResourceEntryName::ID(123));
+    assert_eq!("MAINICON".parse::<ResourceEntryName>().unwrap(), "MAINICON".into());
+    assert!("#12a".parse::<ResourceEntryName>().is_err());
+    assert!("".parse::<ResourceEntryName>().is_err());
+    assert_eq!(format!("{}", ResourceEntryName::ID(123)), "#123");
+    assert_eq!(format!("{}", ResourceEntryName::from("图标")), "图标");
+
+    let mut names: Vec<ResourceEntryName> = vec![
+        3.into(),
+        "beta".into(),
+        1.into(),
+        "ALPHA".into(),
+        "Beta".into(),
+    ];
+    names.sort();
+    assert_eq!(names, vec![
+        ResourceEntryName::from("ALPHA"),
+        "Beta".into(),
+        "beta".into(),
+        1.into(),
+        3.into()
+    ]);
+}
+
+#[test]
+fn resource_paths() {
+    init_logger();
+
+    let data_large = std::fs::read(BINARY_PATH_LARGE).unwrap();
+    let image_large = Image::parse(&data_large[..]).unwrap();
+    let mut resource_directory = image_large.resource_directory().cloned().unwrap();
+
+    let manifest_path = ResourcePath::new(ResourceType::Manifest, 1, LANGUAGE_ID_EN_US);
+    assert_eq!(manifest_path.to_string(), "RT_MANIFEST/#1/1033");
+    let manifest = resource_directory.get_resource(&manifest_path).unwrap().unwrap();
+    assert_eq!(manifest.data(), resource_directory.get_manifest().unwrap().unwrap().as_bytes());
+    assert_eq!(
+        resource_directory
+            .get_resource(&ResourcePath::new(RT_MANIFEST, 1, 1031))
+            .unwrap(),
+        None
+    );
+
+    let paths = resource_directory.resources().map(|(path, _)| path).collect::<Vec<_>>();
+    assert!(paths.contains(&manifest_path));
+    assert!(paths.contains(&ResourcePath::new(ResourceType::GroupIcon, 1, LANGUAGE_ID_EN_US)));
+    let leaves = resource_directory
+        .root()
+        .entries()
+        .into_iter()
+        .map(|type_| {
+            let type_table = resource_directory.root().get(type_).unwrap().as_table().unwrap();
+            type_table
+                .entries()
+                .into_iter()
+                .map(|name| type_table.get(name).unwrap().as_table().unwrap().entries().len())
+                .sum::<usize>()
+        })
+        .sum::<usize>();
+    assert_eq!(paths.len(), leaves, "all leaves are iterated");
+
+    let config_path = ResourcePath::new("CONFIG", "SETTINGS", 1031);
+    resource_directory.set_resource(&config_path, b"key=value".to_vec()).unwrap();
+    assert_eq!(
+        resource_directory.get_resource(&config_path).unwrap().unwrap().data(),
+        b"key=value"
+    );
+    for (path, data) in resource_directory.resources_mut() {
+        if path.type_ == ResourceType::Custom("CONFIG".into()) {
+            data.set_codepage(1252);
+        }
+    }
+    assert_eq!(resource_directory.get_resource(&config_path).unwrap().unwrap().codepage(), 1252);
+    assert_eq!(resource_directory.resources().count(), leaves + 1);
+
+    let removed = resource_directory.remove_resource(&config_path).unwrap().unwrap();
+    assert_eq!(removed.data(), b"key=value");
+    assert!(resource_directory.root().get("CONFIG").is_none(), "empty tables are removed");
+    assert_eq!(resource_directory.remove_resource(&config_path).unwrap(), None);
+}
+
+#[test]
+fn find_resource_language_fallback() {
+    init_logger();
+
+    let mut resource_directory = ResourceDirectory::default();
+    for language in [0x0807, 0x0007, LANG_NEUTRAL, 0x040c, LANGUAGE_ID_EN_US] {
+        let path = ResourcePath::new(ResourceType::RCData, "DATA", language);
+        resource_directory.set_resource(&path, language.to_le_bytes().to_vec()).unwrap();
+    }
+    let find = |resource_directory: &ResourceDirectory, language, user_language| {
+        let found = resource_directory
+            .find_resource(ResourceType::RCData, "DATA", language, user_language)
+            .unwrap()
+            .unwrap();
+        assert_eq!(found.1.data(), found.0.language.to_le_bytes());
+        found.0.language
+    };
+
+    assert_eq!(find(&resource_directory, 0x0807, None), 0x0807, "exact language");
+    assert_eq!(find(&resource_directory, 0x0407, None), 0x0007, "primary language");
+    resource_directory
+        .remove_resource(&ResourcePath::new(RT_RCDATA, "DATA", 0x0007))
+        .unwrap();
+    assert_eq!(
+        find(&resource_directory, 0x0407, Some(0x040c)),
+        LANG_NEUTRAL,
+        "neutral language"
+    );
+    resource_directory
+        .remove_resource(&ResourcePath::new(RT_RCDATA, "DATA", LANG_NEUTRAL))
+        .unwrap();
+    assert_eq!(find(&resource_directory, 0x0407, Some(0x040c)), 0x040c, "user language");
+    assert_eq!(find(&resource_directory, 0x0407, None), LANGUAGE_ID_EN_US, "en-US");
+    resource_directory
+        .remove_resource(&ResourcePath::new(RT_RCDATA, "DATA", LANGUAGE_ID_EN_US))
+        .unwrap();
+    assert_eq!(find(&resource_directory, 0x0407, None), 0x0807, "first language");
+
+    assert!(
+        resource_directory
+            .find_resource(RT_RCDATA, "OTHER", 0x0407, None)
+            .unwrap()
+            .is_none()
+    );
+    assert!(
+        resource_directory
+            .find_resource(RT_HTML, "DATA", 0x0407, None)
+            .unwrap()
+            .is_none()
+    );
+
+    resource_directory
+        .set_manifest_resource(ResourceEntryName::ID(1), 0x0407, "<de/>")
+        .unwrap();
+    resource_directory
+        .set_manifest_resource(ResourceEntryName::ID(1), LANG_NEUTRAL, "<neutral/>")
+        .unwrap();
+    assert_eq!(resource_directory.get_manifest().unwrap().as_deref(), Some("<neutral/>"));
+}
+
 #[test]
 fn get_manifest() {
     init_logger();
@@ -589,3 +1125,1298 @@ fn set_subsystem() {
         "original and rebuilt data equal"
     );
 }
+
+#[test]
+fn set_string_table() {
+    init_logger();
+
+    let data_large = std::fs::read(BINARY_PATH_LARGE).unwrap();
+    let mut image_large = Image::parse(&data_large[..]).unwrap();
+
+    let mut target_resource_directory =
+        image_large.resource_directory().cloned().unwrap_or_default();
+
+    target_resource_directory.set_string(1, LANGUAGE_ID_EN_US, "Hello").unwrap();
+    target_resource_directory.set_string(17, LANGUAGE_ID_EN_US, "Wörld").unwrap();
+    target_resource_directory.set_string(17, 1031, "Welt").unwrap();
+    image_large.set_resource_directory(target_resource_directory.clone()).unwrap();
+
+    let image_rebuilt = Image::parse(image_large.data()).unwrap();
+    let resource_directory = image_rebuilt.resource_directory().unwrap();
+    assert_eq!(
+        resource_directory.get_string(1, LANGUAGE_ID_EN_US).unwrap().as_deref(),
+        Some("Hello"),
+        "string is present after rebuilding"
+    );
+    assert_eq!(
+        resource_directory.get_string(17, LANGUAGE_ID_EN_US).unwrap().as_deref(),
+        Some("Wörld"),
+        "string in second block is present after rebuilding"
+    );
+    assert_eq!(
+        resource_directory.get_string(17, 1031).unwrap().as_deref(),
+        Some("Welt"),
+        "string in other language is present after rebuilding"
+    );
+    assert_eq!(resource_directory.get_string(2, LANGUAGE_ID_EN_US).unwrap(), None);
+
+    assert_eq!(
+        target_resource_directory
+            .remove_string(1, LANGUAGE_ID_EN_US)
+            .unwrap()
+            .as_deref(),
+        Some("Hello"),
+        "removed string is returned"
+    );
+    assert!(
+        target_resource_directory
+            .get_string_table_block(1, LANGUAGE_ID_EN_US)
+            .unwrap()
+            .is_none(),
+        "empty block is removed"
+    );
+    target_resource_directory.remove_string(17, LANGUAGE_ID_EN_US).unwrap();
+    target_resource_directory.remove_string(17, 1031).unwrap();
+    assert!(
+        target_resource_directory
+            .root()
+            .get(ResourceEntryName::ID(RT_STRING as u32))
+            .is_none(),
+        "empty string table is removed"
+    );
+}
+
+#[test]
+fn build_string_table_block() {
+    // block 1 as written by rc.exe for `STRINGTABLE { 1, "OK" 3, "Cancel" }`
+    let mut data = vec![0u8, 0];
+    data.extend([2, 0, b'O', 0, b'K', 0]);
+    data.extend([0, 0]);
+    data.extend([6, 0]);
+    data.extend("Cancel".encode_utf16().flat_map(|c| c.to_le_bytes()));
+    data.extend([0u8; 12 * 2]);
+
+    let block = StringTableBlock::parse(&data).unwrap();
+    assert_eq!(block.strings[1], "OK");
+    assert_eq!(block.strings[3], "Cancel");
+    assert_eq!(block.build().unwrap(), data, "built block equals original block");
+
+    let mut block = block;
+    block.strings[2] = "x".repeat(u16::MAX as usize + 1);
+    assert!(block.build().is_err(), "overlong string is rejected");
+    let mut resource_directory = ResourceDirectory::default();
+    resource_directory.set_string(1, LANGUAGE_ID_EN_US, "OK").unwrap();
+    assert!(resource_directory.set_string(2, LANGUAGE_ID_EN_US, &block.strings[2]).is_err());
+    assert_eq!(resource_directory.get_string(2, LANGUAGE_ID_EN_US).unwrap(), None);
+    assert_eq!(
+        resource_directory.get_string(1, LANGUAGE_ID_EN_US).unwrap().as_deref(),
+        Some("OK")
+    );
+}
+
+fn u16_string(string: &str) -> Vec<u8> {
+    let mut data: Vec<u8> = string.encode_utf16().flat_map(|c| c.to_le_bytes()).collect();
+    data.extend([0, 0]);
+    data
+}
+
+#[test]
+fn build_dialog_template() {
+    // `DIALOG 0, 0, 100, 50 STYLE DS_SETFONT | WS_POPUP CAPTION "About" FONT 8, "MS Shell Dlg"`
+    // `{ DEFPUSHBUTTON "OK", 1, 10, 10, 50, 14 }`
+    let mut data = Vec::new();
+    data.extend((0x80000000u32 | DS_SETFONT).to_le_bytes());
+    data.extend(0u32.to_le_bytes());
+    data.extend(1u16.to_le_bytes());
+    data.extend([0i16, 0, 100, 50].iter().flat_map(|v| v.to_le_bytes()));
+    data.extend([0, 0, 0, 0]);
+    data.extend(u16_string("About"));
+    data.extend(8u16.to_le_bytes());
+    data.extend(u16_string("MS Shell Dlg"));
+    data.extend(vec![0; data.len().next_multiple_of(4) - data.len()]);
+    data.extend(0x50010001u32.to_le_bytes());
+    data.extend(0u32.to_le_bytes());
+    data.extend([10i16, 10, 50, 14].iter().flat_map(|v| v.to_le_bytes()));
+    data.extend(1u16.to_le_bytes());
+    data.extend([0xff, 0xff, 0x80, 0x00]);
+    data.extend(u16_string("OK"));
+    data.extend([0, 0]);
+
+    let mut dialog = DialogTemplate::parse(&data).unwrap();
+    assert!(!dialog.extended, "dialog is a classic dialog template");
+    assert_eq!(dialog.title, "About");
+    assert_eq!(dialog.font.as_ref().unwrap().typeface, "MS Shell Dlg");
+    assert_eq!(dialog.controls.len(), 1);
+    assert_eq!(dialog.controls[0].class, Some(NameOrOrdinal::Ordinal(DIALOG_CLASS_BUTTON)));
+    assert_eq!(dialog.controls[0].title, Some(NameOrOrdinal::Name("OK".to_string())));
+    assert_eq!(dialog.build(), data, "built dialog equals original dialog");
+
+    dialog.extended = true;
+    dialog.font.as_mut().unwrap().weight = 400;
+    dialog.control_mut(1).unwrap().title = Some("Close".into());
+    dialog.controls.push(DialogControl {
+        style: 0x50000000,
+        id: 0x10000,
+        class: Some("SysLink".into()),
+        title: Some("<a>Website</a>".into()),
+        creation_data: vec![1, 2, 3],
+        ..Default::default()
+    });
+    let data_extended = dialog.build();
+    assert_eq!(
+        &data_extended[0..4],
+        &[1, 0, 0xff, 0xff],
+        "dialog is an extended dialog template"
+    );
+    assert_eq!(
+        DialogTemplate::parse(&data_extended).unwrap(),
+        dialog,
+        "parsed dialog equals built dialog"
+    );
+
+    let mut resource_directory = ResourceDirectory::default();
+    resource_directory
+        .set_dialog(ResourceEntryName::ID(100), LANGUAGE_ID_EN_US, &dialog)
+        .unwrap();
+    assert_eq!(
+        resource_directory
+            .get_dialog(ResourceEntryName::ID(100), LANGUAGE_ID_EN_US)
+            .unwrap(),
+        Some(dialog),
+        "dialog is present after setting"
+    );
+    resource_directory
+        .remove_dialog(ResourceEntryName::ID(100), LANGUAGE_ID_EN_US)
+        .unwrap();
+    assert_eq!(resource_directory.size(), ResourceDirectory::default().size());
+}
+
+#[test]
+fn build_menu() {
+    // `MENU { POPUP "&File" { MENUITEM "&Open", 100 MENUITEM SEPARATOR MENUITEM "E&xit", 101 } }`
+    let mut data = vec![0, 0, 0, 0];
+    data.extend((MF_POPUP | MF_END).to_le_bytes());
+    data.extend(u16_string("&File"));
+    data.extend(0u16.to_le_bytes());
+    data.extend(100u16.to_le_bytes());
+    data.extend(u16_string("&Open"));
+    data.extend([0, 0, 0, 0, 0, 0]);
+    data.extend(MF_END.to_le_bytes());
+    data.extend(101u16.to_le_bytes());
+    data.extend(u16_string("E&xit"));
+
+    let mut menu = Menu::parse(&data).unwrap();
+    assert!(!menu.extended, "menu is a normal menu");
+    assert_eq!(menu.items.len(), 1);
+    let submenu = menu.items[0].submenu.as_ref().unwrap();
+    assert_eq!(submenu.len(), 3);
+    assert!(submenu[1].is_separator(), "second item is a separator");
+    assert_eq!(menu.item(101).unwrap().text, "E&xit");
+    assert_eq!(menu.build(), data, "built menu equals original menu");
+
+    menu.extended = true;
+    menu.item_mut(100).unwrap().text = "&Öffnen".to_string();
+    menu.items[0].submenu.as_mut().unwrap()[1].flags = MFT_SEPARATOR;
+    menu.items[0].help_id = 7;
+    menu.items.push(MenuItem {
+        text: "&Help".to_string(),
+        submenu: Some(vec![MenuItem {
+            id: 0x10000,
+            state: MFS_DEFAULT,
+            text: "&About".to_string(),
+            ..Default::default()
+        }]),
+        ..Default::default()
+    });
+    let data_extended = menu.build();
+    assert_eq!(Menu::parse(&data_extended).unwrap(), menu, "parsed menu equals built menu");
+
+    // empty submenus are terminated with a separator and keep the following items intact
+    for extended in [false, true] {
+        let empty = Menu {
+            extended,
+            items: vec![
+                MenuItem {
+                    text: "&Empty".to_string(),
+                    submenu: Some(Vec::new()),
+                    ..Default::default()
+                },
+                MenuItem {
+                    id: 102,
+                    text: "&Next".to_string(),
+                    ..Default::default()
+                },
+            ],
+            ..Default::default()
+        };
+        let parsed = Menu::parse(&empty.build()).unwrap();
+        assert_eq!(parsed.items.len(), 2);
+        let submenu = parsed.items[0].submenu.as_ref().unwrap();
+        assert!(submenu.len() == 1 && submenu[0].is_separator());
+        assert_eq!(parsed.items[1].text, "&Next");
+        let parsed = Menu::parse(
+            &Menu {
+                extended,
+                ..Default::default()
+            }
+            .build(),
+        )
+        .unwrap();
+        assert!(parsed.items.len() == 1 && parsed.items[0].is_separator());
+    }
+
+    let mut resource_directory = ResourceDirectory::default();
+    resource_directory
+        .set_menu(ResourceEntryName::from_string("MAIN"), 1031, &menu)
+        .unwrap();
+    assert_eq!(
+        resource_directory
+            .get_menu(ResourceEntryName::from_string("MAIN"), 1031)
+            .unwrap(),
+        Some(menu),
+        "menu is present after setting"
+    );
+    resource_directory
+        .remove_menu(ResourceEntryName::from_string("MAIN"), 1031)
+        .unwrap();
+    assert!(resource_directory.root().entries().is_empty(), "menu table is removed");
+}
+
+#[test]
+fn build_accelerator_table() {
+    // `ACCELERATORS { "^O", 100 VK_F5, 101, VIRTKEY, CONTROL, SHIFT }`
+    let mut data = Vec::new();
+    data.extend([0x00, 0x00, 0x0f, 0x00, 100, 0x00, 0x00, 0x00]);
+    data.extend([0x8d, 0x00, 0x74, 0x00, 101, 0x00, 0x00, 0x00]);
+
+    let mut table = AcceleratorTable::parse(&data).unwrap();
+    assert_eq!(table.accelerators.len(), 2);
+    assert_eq!(table.accelerators[1].flags, FVIRTKEY | FSHIFT | FCONTROL);
+    assert_eq!(table.build(), data, "built accelerator table equals original table");
+
+    table.accelerators.push(Accelerator {
+        flags: FVIRTKEY | FALT,
+        key:   0x73,
+        id:    102,
+    });
+    let mut resource_directory = ResourceDirectory::default();
+    resource_directory
+        .set_accelerator_table(ResourceEntryName::ID(1), LANGUAGE_ID_EN_US, &table)
+        .unwrap();
+    let table_rebuilt = resource_directory
+        .get_accelerator_table(ResourceEntryName::ID(1), LANGUAGE_ID_EN_US)
+        .unwrap()
+        .unwrap();
+    assert_eq!(table, table_rebuilt, "accelerator table is present after setting");
+    assert_eq!(
+        table_rebuilt.build()[8..16],
+        data[8..16].iter().map(|b| b & !0x80).collect::<Vec<_>>()[..],
+        "last entry flag is moved to the new last entry"
+    );
+
+    resource_directory
+        .set_accelerator_table(ResourceEntryName::ID(1), LANGUAGE_ID_EN_US, &Default::default())
+        .unwrap();
+    assert!(
+        resource_directory.root().entries().is_empty(),
+        "empty accelerator table is removed"
+    );
+}
+
+#[test]
+fn compile_message_table() {
+    let source = r#"
+; // event log messages
+MessageIdTypedef=DWORD
+
+SeverityNames=(Success=0x0:STATUS_SEVERITY_SUCCESS
+               Error=0x3:STATUS_SEVERITY_ERROR
+              )
+FacilityNames=(Runtime=0x2:FACILITY_RUNTIME)
+LanguageNames=(English=0x409:MSG00409
+               German = 0x407 : MSG00407)
+
+MessageId=0x1
+SymbolicName=MSG_STARTED
+Language=English
+Service %1 started.
+.
+Language=German
+Dienst %1 gestartet.
+.
+
+MessageId=
+Severity=Error
+Facility=Runtime
+SymbolicName=MSG_FAILED
+Language=English
+Service %1 failed:
+%2
+.
+"#;
+    let tables = MessageTable::compile(source).unwrap();
+    assert_eq!(tables.len(), 2, "message tables for both languages are compiled");
+    let english = &tables[&LANGUAGE_ID_EN_US];
+    assert_eq!(english.get(0x1), Some("Service %1 started.\r\n"));
+    assert_eq!(english.get(0xc0020001), Some("Service %1 failed:\r\n%2\r\n"));
+    assert_eq!(english.blocks(), vec![(0x1, 0x1), (0xc0020001, 0xc0020001)]);
+    assert_eq!(tables[&1031].get(0x1), Some("Dienst %1 gestartet.\r\n"));
+
+    // message ids without a value continue from the last id of their facility
+    let source = r#"
+FacilityNames=(Io=0x1 Net=0x2)
+MessageId=0x10
+Facility=Io
+Language=English
+A
+.
+MessageId=0x20
+Facility=Net
+Language=English
+B
+.
+MessageId=
+Facility=Io
+Language=English
+C
+.
+MessageId=+2
+Facility=Net
+Language=English
+D
+.
+MessageId=
+Facility=Io
+Language=English
+E
+.
+"#;
+    let tables = MessageTable::compile(source).unwrap();
+    assert_eq!(tables[&LANGUAGE_ID_EN_US].entries.keys().copied().collect::<Vec<_>>(), vec![
+        0x10010, 0x10011, 0x10012, 0x20020, 0x20022
+    ]);
+
+    let mut table = MessageTable::default();
+    table.insert(1, "Hi\r\n");
+    let mut data = vec![1, 0, 0, 0, 1, 0, 0, 0, 1, 0, 0, 0, 16, 0, 0, 0, 16, 0, 1, 0];
+    data.extend(u16_string("Hi\r\n"));
+    data.extend([0, 0]);
+    assert_eq!(table.build(), data, "built message table equals expected layout");
+
+    table.entries.insert(2, MessageTableEntry {
+        text:    "Ansi\r\n".to_string(),
+        unicode: false,
+    });
+    let data = table.build();
+    assert_eq!(
+        MessageTable::parse(&data).unwrap(),
+        table,
+        "parsed message table equals built table"
+    );
+
+    let mut resource_directory = ResourceDirectory::default();
+    resource_directory
+        .set_message_table(ResourceEntryName::ID(1), LANGUAGE_ID_EN_US, english)
+        .unwrap();
+    assert_eq!(
+        resource_directory
+            .get_message_table(ResourceEntryName::ID(1), LANGUAGE_ID_EN_US)
+            .unwrap()
+            .as_ref(),
+        Some(english),
+        "message table is present after setting"
+    );
+}
+
+fn dib(size: u32) -> Vec<u8> {
+    let mut data = Vec::new();
+    data.extend(40u32.to_le_bytes());
+    data.extend((size as i32).to_le_bytes());
+    data.extend((size as i32 * 2).to_le_bytes());
+    data.extend(1u16.to_le_bytes());
+    data.extend(32u16.to_le_bytes());
+    data.extend([0u8; 24]);
+    data.extend(vec![0x7f; (size * size * 4) as usize]);
+    data.extend(vec![0x00; (size * size.div_ceil(32) * 4) as usize]);
+    data
+}
+
+#[test]
+fn set_cursor() {
+    init_logger();
+
+    let images = [(dib(32), 3u16, 5u16), (dib(16), 1, 2)];
+    let mut data = vec![0, 0, 2, 0, images.len() as u8, 0];
+    let mut offset = 6 + images.len() * 16;
+    for (image, x, y) in &images {
+        let size = u32::from_le_bytes(image[4..8].try_into().unwrap()) as u8;
+        data.extend([size, size, 0, 0]);
+        data.extend(x.to_le_bytes());
+        data.extend(y.to_le_bytes());
+        data.extend((image.len() as u32).to_le_bytes());
+        data.extend((offset as u32).to_le_bytes());
+        offset += image.len();
+    }
+    for (image, ..) in &images {
+        data.extend(image);
+    }
+
+    let cursor = CursorFile::parse(&data).unwrap();
+    assert_eq!(cursor.images.len(), 2);
+    assert_eq!((cursor.images[0].hotspot_x, cursor.images[0].hotspot_y), (3, 5));
+    assert_eq!(cursor.build().unwrap(), data, "built cursor file equals original file");
+
+    let data_large = std::fs::read(BINARY_PATH_LARGE).unwrap();
+    let mut image_large = Image::parse(&data_large[..]).unwrap();
+    let mut resource_directory = image_large.resource_directory().cloned().unwrap_or_default();
+
+    let name = ResourceEntryName::from_string("BUSY");
+    resource_directory.set_cursor(&name, LANGUAGE_ID_EN_US, &cursor).unwrap();
+    resource_directory
+        .set_cursor(ResourceEntryName::ID(2), LANGUAGE_ID_EN_US, &cursor)
+        .unwrap();
+    image_large.set_resource_directory(resource_directory.clone()).unwrap();
+
+    let image_rebuilt = Image::parse(image_large.data()).unwrap();
+    let resource_directory_rebuilt = image_rebuilt.resource_directory().unwrap();
+    assert_eq!(
+        resource_directory_rebuilt.get_cursor(&name, LANGUAGE_ID_EN_US).unwrap(),
+        Some(cursor.clone()),
+        "cursor is present after rebuilding"
+    );
+    let group = resource_directory_rebuilt
+        .root()
+        .get(ResourceEntryName::ID(RT_GROUP_CURSOR as u32))
+        .unwrap()
+        .as_table()
+        .unwrap()
+        .get(&name)
+        .unwrap()
+        .as_table()
+        .unwrap()
+        .get(ResourceEntryName::ID(LANGUAGE_ID_EN_US as u32))
+        .unwrap()
+        .as_data()
+        .unwrap()
+        .data()
+        .to_vec();
+    assert_eq!(&group[6..14], &[32, 0, 64, 0, 1, 0, 32, 0], "group entry contains dimensions");
+    assert_eq!(
+        u32::from_le_bytes(group[14..18].try_into().unwrap()) as usize,
+        images[0].0.len() + 4,
+        "group entry size includes hotspot"
+    );
+
+    // replacing a cursor group removes its previous images
+    resource_directory.set_cursor(&name, LANGUAGE_ID_EN_US, &cursor).unwrap();
+    let cursor_table = resource_directory
+        .root()
+        .get(ResourceEntryName::ID(RT_CURSOR as u32))
+        .unwrap()
+        .as_table()
+        .unwrap();
+    assert_eq!(cursor_table.entries().len(), 4, "replaced cursor images are removed");
+
+    // the doubled height of a png frame must fit into the group entry
+    let mut png = b"\x89PNG\r\n\x1a\n\0\0\0\x0dIHDR".to_vec();
+    png.extend(64u32.to_be_bytes());
+    png.extend(40000u32.to_be_bytes());
+    png.extend([8, 6, 0, 0, 0]);
+    let large = CursorFile {
+        images: vec![CursorImage {
+            hotspot_x: 0,
+            hotspot_y: 0,
+            data:      png,
+        }],
+    };
+    assert!(matches!(
+        resource_directory.set_cursor(&name, LANGUAGE_ID_EN_US, &large),
+        Err(ResourceError::InvalidBytes(_))
+    ));
+
+    resource_directory.remove_cursor(&name, LANGUAGE_ID_EN_US).unwrap();
+    resource_directory
+        .remove_cursor(ResourceEntryName::ID(2), LANGUAGE_ID_EN_US)
+        .unwrap();
+    assert!(
+        resource_directory.root().get(ResourceEntryName::ID(RT_CURSOR as u32)).is_none(),
+        "cursor table is removed"
+    );
+    assert!(
+        resource_directory
+            .root()
+            .get(ResourceEntryName::ID(RT_GROUP_CURSOR as u32))
+            .is_none(),
+        "cursor group table is removed"
+    );
+}
+
+#[test]
+fn set_animated_cursor() {
+    init_logger();
+
+    let data_icon = std::fs::read(BINARY_PATH_ICON).unwrap();
+    let icon = image::load_from_memory(&data_icon[..]).unwrap();
+    let animated_icon = AnimatedCursor::from_icons(&[icon.clone(), icon.fliph()], 10).unwrap();
+    assert_eq!(animated_icon.frames.len(), 2);
+    assert_eq!(
+        animated_icon.frame_icons(0).unwrap().unwrap().len(),
+        icon.icons().unwrap().len(),
+        "icon frame contains all icon resolutions"
+    );
+
+    let mut animated =
+        AnimatedCursor::from_images(&[icon.clone(), icon.fliph()], 32, (3, 5), 10).unwrap();
+    assert_eq!(animated.frames.len(), 2);
+    for frame in &animated.frames {
+        let frame = CursorFile::parse(frame).unwrap();
+        assert_eq!(frame.images.len(), 1, "cursor frame contains the cursor size");
+        assert_eq!((frame.images[0].hotspot_x, frame.images[0].hotspot_y), (3, 5));
+        assert_eq!(frame.images[0].dimensions().unwrap(), (32, 32, 1, 32));
+    }
+    assert!(AnimatedCursor::from_images(std::slice::from_ref(&icon), 32, (32, 0), 10).is_err());
+
+    let cursor = CursorFile {
+        images: vec![CursorImage {
+            hotspot_x: 3,
+            hotspot_y: 5,
+            data:      dib(32),
+        }],
+    };
+    let from_cursors = AnimatedCursor::from_cursors(std::slice::from_ref(&cursor), 10).unwrap();
+    assert_eq!(from_cursors.frames, vec![cursor.build().unwrap()]);
+    animated.frames.push(cursor.build().unwrap());
+    animated.rates = Some(vec![10, 20, 10, 30]);
+    animated.sequence = Some(vec![0, 1, 2, 1]);
+    animated.title = Some("Busy".to_string());
+    animated.artist = Some("editpe".to_string());
+
+    let data = animated.build();
+    assert_eq!(&data[0..4], b"RIFF");
+    assert_eq!(&data[8..12], b"ACON");
+    assert_eq!(AnimatedCursor::parse(&data).unwrap(), animated, "parsed cursor equals original");
+
+    let cursor_icons = animated.frame_icons(2).unwrap().unwrap();
+    assert_eq!(&cursor_icons[0][0..8], &[32, 32, 0, 0, 1, 0, 32, 0], "cursor frame is an icon");
+    assert_eq!(animated.frame_icons(3).unwrap(), None);
+
+    let data_large = std::fs::read(BINARY_PATH_LARGE).unwrap();
+    let mut image_large = Image::parse(&data_large[..]).unwrap();
+    let mut resource_directory = image_large.resource_directory().cloned().unwrap_or_default();
+
+    resource_directory
+        .set_animated_cursor(ResourceEntryName::ID(1), LANGUAGE_ID_EN_US, &animated)
+        .unwrap();
+    resource_directory
+        .set_animated_icon(ResourceEntryName::ID(1), LANGUAGE_ID_EN_US, &animated)
+        .unwrap();
+    image_large.set_resource_directory(resource_directory.clone()).unwrap();
+
+    let image_rebuilt = Image::parse(image_large.data()).unwrap();
+    let resource_directory_rebuilt = image_rebuilt.resource_directory().unwrap();
+    assert_eq!(
+        resource_directory_rebuilt
+            .get_animated_cursor(ResourceEntryName::ID(1), LANGUAGE_ID_EN_US)
+            .unwrap(),
+        Some(animated.clone()),
+        "animated cursor is present after rebuilding"
+    );
+    assert_eq!(
+        resource_directory_rebuilt
+            .get_animated_icon(ResourceEntryName::ID(1), LANGUAGE_ID_EN_US)
+            .unwrap(),
+        Some(animated),
+        "animated icon is present after rebuilding"
+    );
+
+    resource_directory
+        .remove_animated_cursor(ResourceEntryName::ID(1), LANGUAGE_ID_EN_US)
+        .unwrap();
+    assert!(
+        resource_directory
+            .root()
+            .get(ResourceEntryName::ID(RT_ANICURSOR as u32))
+            .is_none(),
+        "animated cursor table is removed"
+    );
+}
+
+#[test]
+fn set_bitmap() {
+    init_logger();
+
+    let data_icon = std::fs::read(BINARY_PATH_ICON).unwrap();
+    let icon = image::load_from_memory(&data_icon[..]).unwrap().thumbnail_exact(13, 7);
+    let icon_rgb = image::DynamicImage::ImageRgb8(icon.to_rgb8());
+
+    let data_large = std::fs::read(BINARY_PATH_LARGE).unwrap();
+    let mut image_large = Image::parse(&data_large[..]).unwrap();
+    let mut resource_directory = image_large.resource_directory().cloned().unwrap_or_default();
+
+    let name = ResourceEntryName::from_string("SPLASH");
+    resource_directory.set_bitmap(&name, LANGUAGE_ID_EN_US, &icon).unwrap();
+    resource_directory
+        .set_bitmap(ResourceEntryName::ID(2), LANGUAGE_ID_EN_US, &icon_rgb)
+        .unwrap();
+    image_large.set_resource_directory(resource_directory.clone()).unwrap();
+
+    let image_rebuilt = Image::parse(image_large.data()).unwrap();
+    let resource_directory_rebuilt = image_rebuilt.resource_directory().unwrap();
+    let bitmap = resource_directory_rebuilt
+        .get_bitmap(&name, LANGUAGE_ID_EN_US)
+        .unwrap()
+        .unwrap();
+    assert_eq!(&bitmap[0..2], b"BM", "bitmap is a bmp file");
+    assert_eq!(u16::from_le_bytes(bitmap[28..30].try_into().unwrap()), 32);
+    assert_eq!(
+        resource_directory_rebuilt
+            .get_bitmap_image(&name, LANGUAGE_ID_EN_US)
+            .unwrap()
+            .unwrap(),
+        image::DynamicImage::ImageRgba8(icon.to_rgba8()),
+        "decoded bitmap equals original image"
+    );
+    let bitmap_rgb = resource_directory_rebuilt
+        .get_bitmap(ResourceEntryName::ID(2), LANGUAGE_ID_EN_US)
+        .unwrap()
+        .unwrap();
+    assert_eq!(u16::from_le_bytes(bitmap_rgb[28..30].try_into().unwrap()), 24);
+    assert_eq!(
+        image::load_from_memory(&bitmap_rgb).unwrap(),
+        icon_rgb,
+        "decoded bitmap equals original image"
+    );
+
+    // setting a bmp file stores the bitmap without the file header
+    resource_directory.set_bitmap(&name, LANGUAGE_ID_EN_US, &bitmap[..]).unwrap();
+    assert_eq!(resource_directory.get_bitmap(&name, LANGUAGE_ID_EN_US).unwrap(), Some(bitmap));
+    assert!(resource_directory.set_bitmap(&name, LANGUAGE_ID_EN_US, &data_icon[..]).is_err());
+
+    resource_directory.remove_bitmap(&name, LANGUAGE_ID_EN_US).unwrap();
+    resource_directory
+        .remove_bitmap(ResourceEntryName::ID(2), LANGUAGE_ID_EN_US)
+        .unwrap();
+    assert!(resource_directory.root().get(ResourceEntryName::ID(RT_BITMAP as u32)).is_none());
+}
+
+#[test]
+fn get_icon_file() {
+    init_logger();
+
+    let data_large = std::fs::read(BINARY_PATH_LARGE).unwrap();
+    let image_large = Image::parse(&data_large[..]).unwrap();
+    let resource_directory = image_large.resource_directory().cloned().unwrap_or_default();
+
+    let icon_file = resource_directory.get_main_icon_file().unwrap().unwrap();
+    assert_eq!(
+        resource_directory
+            .get_icon_file(ResourceEntryName::ID(1), LANGUAGE_ID_EN_US)
+            .unwrap(),
+        Some(icon_file.clone()),
+        "main icon group is the first icon group"
+    );
+    let icons = icon_file.icons().unwrap();
+    assert!(!icons.is_empty(), "icon file contains images");
+    assert_eq!(
+        &icons[0][14..],
+        resource_directory.get_main_icon().unwrap().unwrap(),
+        "first image equals main icon"
+    );
+
+    let images = resource_directory.get_main_icon_images().unwrap().unwrap();
+    assert_eq!(images.len(), icons.len(), "all images are decoded");
+    for (image, icon) in images.iter().zip(&icons) {
+        let size = |size: u8| if size == 0 { 256 } else { size as u32 };
+        assert_eq!((image.width(), image.height()), (size(icon[0]), size(icon[1])));
+    }
+
+    assert_eq!(
+        resource_directory
+            .get_icon_file(ResourceEntryName::ID(2), LANGUAGE_ID_EN_US)
+            .unwrap(),
+        None
+    );
+}
+
+#[test]
+fn set_icon_group() {
+    init_logger();
+
+    let data_large = std::fs::read(BINARY_PATH_LARGE).unwrap();
+    let mut image_large = Image::parse(&data_large[..]).unwrap();
+    let mut resource_directory = image_large.resource_directory().cloned().unwrap_or_default();
+
+    let groups = resource_directory.icon_groups().unwrap();
+    assert_eq!(groups.len(), 1);
+    assert_eq!(
+        (&groups[0].name, groups[0].language),
+        (&ResourceEntryName::ID(1), LANGUAGE_ID_EN_US)
+    );
+    assert!(!groups[0].entries.is_empty(), "icon group contains entries");
+
+    let data_icon = std::fs::read(BINARY_PATH_ICON).unwrap();
+    let icon = image::load_from_memory(&data_icon[..]).unwrap();
+    let icon_file = resource_directory.get_main_icon_file().unwrap().unwrap();
+    let group_names = |resource_directory: &ResourceDirectory| {
+        resource_directory
+            .icon_groups()
+            .unwrap()
+            .into_iter()
+            .map(|group| group.name)
+            .collect::<Vec<_>>()
+    };
+    let name = ResourceEntryName::from_string("DOCICON");
+    resource_directory.set_icon(&name, 0x0407, &icon).unwrap();
+    resource_directory
+        .set_icon(ResourceEntryName::ID(1), LANGUAGE_ID_EN_US, &icon)
+        .unwrap();
+    assert_eq!(group_names(&resource_directory), vec![ResourceEntryName::ID(1), name.clone()]);
+    resource_directory.set_icon(&name, 0x0407, &icon_file[..]).unwrap();
+    assert_eq!(
+        group_names(&resource_directory),
+        vec![ResourceEntryName::ID(1), name.clone()],
+        "replaced groups keep their position"
+    );
+    image_large.set_resource_directory(resource_directory.clone()).unwrap();
+
+    let image_rebuilt = Image::parse(image_large.data()).unwrap();
+    let resource_directory_rebuilt = image_rebuilt.resource_directory().unwrap();
+    let groups = resource_directory_rebuilt.icon_groups().unwrap();
+    assert_eq!(groups.len(), 2);
+    let group = groups.iter().find(|group| group.name == ResourceEntryName::ID(1)).unwrap();
+    assert_eq!(group.entries.len(), icon.icons().unwrap().len());
+    assert_eq!(
+        resource_directory_rebuilt.get_icon_file(&name, 0x0407).unwrap(),
+        Some(icon_file),
+        "icon group is present after rebuilding"
+    );
+    let icon_count = |resource_directory: &ResourceDirectory| {
+        resource_directory
+            .root()
+            .get(ResourceEntryName::ID(RT_ICON as u32))
+            .map(|table| table.as_table().unwrap().entries().len())
+            .unwrap_or_default()
+    };
+    assert_eq!(
+        icon_count(resource_directory_rebuilt),
+        groups.iter().map(|group| group.entries.len()).sum::<usize>(),
+        "replaced icon images are removed"
+    );
+
+    let mut exhausted = resource_directory.clone();
+    exhausted
+        .set_resource(&ResourcePath::new(ResourceType::Icon, u16::MAX, LANGUAGE_ID_EN_US), vec![0])
+        .unwrap();
+    assert!(
+        exhausted.set_icon(&name, 0x0407, &icon).is_err(),
+        "icon ids beyond 16 bits are rejected"
+    );
+    assert!(exhausted.set_main_icon(&icon).is_err());
+    assert_eq!(
+        exhausted.get_icon_file(&name, 0x0407).unwrap(),
+        resource_directory.get_icon_file(&name, 0x0407).unwrap(),
+        "icon group is kept"
+    );
+
+    resource_directory.remove_icon(&name, 0x0407).unwrap();
+    resource_directory
+        .remove_icon(ResourceEntryName::ID(1), LANGUAGE_ID_EN_US)
+        .unwrap();
+    assert!(resource_directory.icon_groups().unwrap().is_empty());
+    assert_eq!(icon_count(&resource_directory), 0, "icon table is removed");
+}
+
+#[test]
+fn remove_unreferenced_images() {
+    init_logger();
+
+    let data_large = std::fs::read(BINARY_PATH_LARGE).unwrap();
+    let mut image_large = Image::parse(&data_large[..]).unwrap();
+    let mut resource_directory = image_large.resource_directory().cloned().unwrap_or_default();
+
+    let icon_table = |resource_directory: &ResourceDirectory| {
+        resource_directory
+            .root()
+            .get(ResourceEntryName::ID(RT_ICON as u32))
+            .unwrap()
+            .as_table()
+            .unwrap()
+            .entries()
+            .into_iter()
+            .cloned()
+            .collect::<Vec<_>>()
+    };
+    let icons_before = icon_table(&resource_directory);
+    assert!(resource_directory.remove_unreferenced_images(true).unwrap().is_empty());
+
+    let data_icon = std::fs::read(BINARY_PATH_ICON).unwrap();
+    let icon = image::load_from_memory(&data_icon[..]).unwrap();
+    resource_directory.set_main_icon(&icon).unwrap();
+    let icon_file = resource_directory.get_main_icon_file().unwrap().unwrap();
+    resource_directory.set_main_icon(&icon).unwrap();
+    let icon_count = icon.icons().unwrap().len();
+    assert_eq!(icon_table(&resource_directory).len(), icons_before.len() + 2 * icon_count);
+
+    let removed = resource_directory.remove_unreferenced_images(false).unwrap();
+    assert_eq!(
+        removed.icons,
+        (icons_before.len() as u32 + 1..=(icons_before.len() + icon_count) as u32)
+            .map(ResourceEntryName::ID)
+            .collect::<Vec<_>>(),
+        "overwritten main icon images are removed"
+    );
+    assert!(removed.cursors.is_empty());
+
+    resource_directory.set_main_icon(&icon).unwrap();
+    let removed = resource_directory.remove_unreferenced_images(true).unwrap();
+    assert_eq!(removed.icons.len(), icon_count);
+    let icons_after = icon_table(&resource_directory);
+    assert_eq!(
+        icons_after,
+        (1..=(icons_before.len() + icon_count) as u32)
+            .map(ResourceEntryName::ID)
+            .collect::<Vec<_>>(),
+        "remaining images are renumbered"
+    );
+    image_large.set_resource_directory(resource_directory.clone()).unwrap();
+
+    // the directory header of the renumbered table counts the remaining images
+    let section = resource_directory.build(0);
+    let read_u16 = |offset: usize| u16::from_le_bytes([section[offset], section[offset + 1]]);
+    let read_u32 =
+        |offset: usize| u32::from_le_bytes(section[offset..offset + 4].try_into().unwrap());
+    let root_entries = (read_u16(12) + read_u16(14)) as usize;
+    let icon_table_offset = (0..root_entries)
+        .find(|i| read_u32(16 + i * 8) == RT_ICON as u32)
+        .map(|i| (read_u32(20 + i * 8) & 0x7fffffff) as usize)
+        .unwrap();
+    assert_eq!(read_u16(icon_table_offset + 12), 0);
+    assert_eq!(read_u16(icon_table_offset + 14) as usize, icons_after.len());
+
+    let image_rebuilt = Image::parse(image_large.data()).unwrap();
+    let resource_directory_rebuilt = image_rebuilt.resource_directory().unwrap();
+    assert_eq!(icon_table(resource_directory_rebuilt), icons_after);
+    assert_eq!(
+        resource_directory_rebuilt.get_main_icon_file().unwrap(),
+        Some(icon_file),
+        "main icon is intact after renumbering"
+    );
+}
+
+#[test]
+fn encode_icon_options() {
+    init_logger();
+
+    let data_icon = std::fs::read(BINARY_PATH_ICON).unwrap();
+    let icon = image::load_from_memory(&data_icon[..]).unwrap();
+
+    let options = IconOptions::new()
+        .sizes(&[256, 32, 16])
+        .png_from(256)
+        .filter(FilterType::Nearest);
+    let icon_file = options.encode(&icon).unwrap();
+    let icons = icon_file.icons().unwrap();
+    assert_eq!(icons.len(), 3);
+    assert_eq!(&icons[0][..8], &[0, 0, 0, 0, 1, 0, 32, 0]);
+    assert_eq!(&icons[0][14..18], b"\x89PNG", "large image is a png image");
+    assert_eq!(&icons[1][..8], &[32, 32, 0, 0, 1, 0, 32, 0]);
+    assert_eq!(&icons[1][14..18], &40u32.to_le_bytes(), "small image is a bitmap");
+    assert_eq!(&icons[1][22..26], &64u32.to_le_bytes(), "bitmap height includes mask");
+    assert_eq!(icons[1].len(), 14 + 40 + 32 * 32 * 4 + 32 * 4, "bitmap contains mask");
+
+    let mut resource_directory = ResourceDirectory::default();
+    resource_directory
+        .set_icon(ResourceEntryName::ID(1), LANGUAGE_ID_EN_US, icon_file)
+        .unwrap();
+    let images = resource_directory
+        .get_icon_images(ResourceEntryName::ID(1), LANGUAGE_ID_EN_US)
+        .unwrap()
+        .unwrap();
+    assert_eq!(
+        images.iter().map(|image| image.width()).collect::<Vec<_>>(),
+        vec![256, 32, 16],
+        "icon images are decodable"
+    );
+    assert_eq!(
+        images[2].to_rgba8(),
+        icon.resize_exact(16, 16, FilterType::Nearest).to_rgba8(),
+        "bitmap image is pixel-exact"
+    );
+
+    let icons = IconOptions::new()
+        .sizes(&[16])
+        .format(16, IconFormat::Bitmap)
+        .bit_depth(24)
+        .encode(&icon)
+        .unwrap()
+        .icons()
+        .unwrap();
+    assert_eq!(&icons[0][..8], &[16, 16, 0, 0, 1, 0, 24, 0]);
+    assert_eq!(icons[0].len(), 14 + 40 + 16 * 16 * 3 + 16 * 4);
+    assert!(IconOptions::new().bit_depth(8).encode(&icon).is_err());
+    assert!(IconOptions::new().sizes(&[512]).encode(&icon).is_err());
+}
+
+#[test]
+fn set_icon_file_keeps_images() {
+    init_logger();
+
+    let solid = |size: u32, color: [u8; 4]| {
+        image::DynamicImage::ImageRgba8(image::RgbaImage::from_pixel(
+            size,
+            size,
+            image::Rgba(color),
+        ))
+    };
+    let red = IconOptions::new()
+        .sizes(&[16])
+        .png_from(256)
+        .encode(&solid(16, [255, 0, 0, 255]));
+    let blue = IconOptions::new().sizes(&[32]).encode(&solid(32, [0, 0, 255, 255]));
+    let icons = [
+        red.unwrap().icons().unwrap(),
+        blue.unwrap().icons().unwrap(),
+    ]
+    .concat();
+    let mut data = vec![0, 0, 1, 0, 2, 0];
+    let mut offset = 6 + 2 * 16;
+    for icon in &icons {
+        data.extend(&icon[..8]);
+        data.extend(((icon.len() - 14) as u32).to_le_bytes());
+        data.extend((offset as u32).to_le_bytes());
+        offset += icon.len() - 14;
+    }
+    for icon in &icons {
+        data.extend(&icon[14..]);
+    }
+    let path = std::env::temp_dir().join("editpe-set-icon-file-keeps-images.ico");
+    std::fs::write(&path, &data).unwrap();
+
+    let mut resource_directory = ResourceDirectory::default();
+    resource_directory.set_main_icon_file(path.to_str().unwrap()).unwrap();
+    std::fs::remove_file(&path).unwrap();
+
+    let icon_file = resource_directory.get_main_icon_file().unwrap().unwrap();
+    let result = icon_file.icons().unwrap();
+    assert_eq!(
+        result.iter().map(|icon| icon[0]).collect::<Vec<_>>(),
+        vec![16, 32, 0, 128, 48, 24],
+        "original images are kept and missing sizes are added"
+    );
+    assert_eq!(&result[0][14..], &icons[0][14..], "original image is unchanged");
+    assert_eq!(&result[1][14..], &icons[1][14..], "original image is unchanged");
+
+    let images = resource_directory.get_main_icon_images().unwrap().unwrap();
+    assert_eq!(
+        images[5].to_rgba8().get_pixel(12, 12).0,
+        [0, 0, 255, 255],
+        "24 is resized from 32"
+    );
+    assert_eq!(
+        images[2].to_rgba8().get_pixel(128, 128).0,
+        [0, 0, 255, 255],
+        "256 is resized from 32"
+    );
+}
+
+#[test]
+fn edit_manifest() {
+    init_logger();
+
+    let data_large = std::fs::read(BINARY_PATH_LARGE).unwrap();
+    let image_large = Image::parse(&data_large[..]).unwrap();
+    let resource_directory = image_large.resource_directory().unwrap();
+    let original = resource_directory.get_manifest().unwrap().unwrap();
+    let manifest = Manifest::parse(&original).unwrap();
+    assert_eq!(manifest.to_string(), original, "unmodified manifest equals original");
+
+    let original = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<assembly xmlns="urn:schemas-microsoft-com:asm.v1" manifestVersion="1.0">
+  <!-- keep me -->
+  <file name='data.dll' custom = "a &amp; b"/>
+  <asmv3:application xmlns:asmv3="urn:schemas-microsoft-com:asm.v3">
+    <asmv3:windowsSettings>
+      <dpiAware xmlns="http://schemas.microsoft.com/SMI/2005/WindowsSettings">true</dpiAware>
+    </asmv3:windowsSettings>
+  </asmv3:application>
+</assembly>
+"#;
+    let mut manifest: Manifest = original.parse().unwrap();
+    assert_eq!(manifest.to_string(), original);
+    assert_eq!(manifest.dpi_aware().as_deref(), Some("true"));
+    assert_eq!(manifest.execution_level(), None);
+    assert!(!manifest.common_controls());
+
+    manifest.set_assembly_identity(Some(&AssemblyIdentity {
+        type_: Some("win32".to_string()),
+        name: Some("Company.Product".to_string()),
+        version: Some("1.2.3.4".to_string()),
+        ..Default::default()
+    }));
+    manifest.set_execution_level(Some(ExecutionLevel::RequireAdministrator));
+    manifest.set_ui_access(false);
+    manifest.set_dpi_aware(Some("true/pm"));
+    manifest.set_dpi_awareness(Some("PerMonitorV2"));
+    manifest.set_long_path_aware(Some(true));
+    manifest.set_active_code_page(Some(MANIFEST_CODE_PAGE_UTF8));
+    manifest.set_heap_type(Some("SegmentHeap"));
+    manifest.set_supported_os(&[SUPPORTED_OS_WINDOWS_7, SUPPORTED_OS_WINDOWS_10]);
+    manifest.set_common_controls(true);
+
+    let expected = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<assembly xmlns="urn:schemas-microsoft-com:asm.v1" manifestVersion="1.0">
+  <!-- keep me -->
+  <assemblyIdentity type="win32" name="Company.Product" version="1.2.3.4"/>
+  <file name='data.dll' custom = "a &amp; b"/>
+  <asmv3:application xmlns:asmv3="urn:schemas-microsoft-com:asm.v3">
+    <asmv3:windowsSettings>
+      <dpiAware xmlns="http://schemas.microsoft.com/SMI/2005/WindowsSettings">true/pm</dpiAware>
+      <dpiAwareness xmlns="http://schemas.microsoft.com/SMI/2016/WindowsSettings">PerMonitorV2</dpiAwareness>
+      <longPathAware xmlns="http://schemas.microsoft.com/SMI/2016/WindowsSettings">true</longPathAware>
+      <activeCodePage xmlns="http://schemas.microsoft.com/SMI/2019/WindowsSettings">UTF-8</activeCodePage>
+      <heapType xmlns="http://schemas.microsoft.com/SMI/2020/WindowsSettings">SegmentHeap</heapType>
+    </asmv3:windowsSettings>
+  </asmv3:application>
+  <trustInfo xmlns="urn:schemas-microsoft-com:asm.v3">
+    <security>
+      <requestedPrivileges>
+        <requestedExecutionLevel level="requireAdministrator" uiAccess="false"/>
+      </requestedPrivileges>
+    </security>
+  </trustInfo>
+  <compatibility xmlns="urn:schemas-microsoft-com:compatibility.v1">
+    <application>
+      <supportedOS Id="{35138b9a-5d96-4fbd-8e2d-a2440225f93a}"/>
+      <supportedOS Id="{8e0f7a12-bfb3-4fe8-b9a5-48fd50a15a9a}"/>
+    </application>
+  </compatibility>
+  <dependency>
+    <dependentAssembly>
+      <assemblyIdentity type="win32" name="Microsoft.Windows.Common-Controls" version="6.0.0.0" processorArchitecture="*" publicKeyToken="6595b64144ccf1df" language="*"/>
+    </dependentAssembly>
+  </dependency>
+</assembly>
+"#;
+    assert_eq!(manifest.to_string(), expected, "settings are added and unknown content is kept");
+    assert_eq!(manifest.execution_level(), Some(ExecutionLevel::RequireAdministrator));
+    assert!(!manifest.ui_access());
+    assert_eq!(manifest.long_path_aware(), Some(true));
+    assert_eq!(manifest.supported_os(), vec![SUPPORTED_OS_WINDOWS_7, SUPPORTED_OS_WINDOWS_10]);
+    assert!(manifest.common_controls());
+    assert_eq!(manifest.assembly_identity().unwrap().name.as_deref(), Some("Company.Product"));
+
+    manifest.set_assembly_identity(None);
+    manifest.set_execution_level(None);
+    manifest.set_dpi_awareness(None);
+    manifest.set_long_path_aware(None);
+    manifest.set_active_code_page(None);
+    manifest.set_heap_type(None);
+    manifest.set_dpi_aware(Some("true"));
+    manifest.set_supported_os::<&str>(&[]);
+    manifest.set_common_controls(false);
+    assert_eq!(manifest.to_string(), original, "removed settings restore the original manifest");
+
+    // new elements use the namespace prefix of the assembly element
+    let original = r#"<asmv1:assembly xmlns:asmv1="urn:schemas-microsoft-com:asm.v1" manifestVersion="1.0">
+</asmv1:assembly>"#;
+    let mut manifest = Manifest::parse(original).unwrap();
+    manifest.set_assembly_identity(Some(&AssemblyIdentity {
+        name: Some("Company.Product".to_string()),
+        ..Default::default()
+    }));
+    manifest.set_common_controls(true);
+    let manifest = manifest.to_string();
+    for element in ["assemblyIdentity", "dependency", "dependentAssembly"] {
+        assert!(manifest.contains(&format!("<asmv1:{}", element)), "{} has a prefix", element);
+        assert!(!manifest.contains(&format!("<{}", element)), "{} has a prefix", element);
+    }
+    assert!(Manifest::parse(&manifest).unwrap().common_controls());
+
+    assert!(Manifest::parse("<assembly><file></assembly>").is_err());
+    assert!(Manifest::parse("<other/>").is_err());
+}
+
+#[test]
+fn set_manifest_resource() {
+    init_logger();
+
+    let data_large = std::fs::read(BINARY_PATH_LARGE).unwrap();
+    let mut image_large = Image::parse(&data_large[..]).unwrap();
+    let mut resource_directory = image_large.resource_directory().cloned().unwrap();
+    let original = resource_directory.get_manifest().unwrap().unwrap();
+    let id = |id: WORD| ResourceEntryName::ID(id as u32);
+    assert_eq!(resource_directory.manifests().unwrap(), vec![(id(1), LANGUAGE_ID_EN_US)]);
+    assert_eq!(
+        resource_directory
+            .get_manifest_resource(id(1), LANGUAGE_ID_EN_US)
+            .unwrap()
+            .as_deref(),
+        Some(original.as_str())
+    );
+
+    let manifest = Manifest::default().to_string();
+    resource_directory
+        .set_manifest_resource(id(ISOLATIONAWARE_MANIFEST_RESOURCE_ID), 1031, &manifest)
+        .unwrap();
+    image_large.set_resource_directory(resource_directory).unwrap();
+    let data_rebuilt = image_large.data().to_vec();
+    let image_rebuilt = Image::parse(&data_rebuilt[..]).unwrap();
+    let mut resource_directory = image_rebuilt.resource_directory().cloned().unwrap();
+    assert_eq!(resource_directory.manifests().unwrap(), vec![
+        (id(1), LANGUAGE_ID_EN_US),
+        (id(ISOLATIONAWARE_MANIFEST_RESOURCE_ID), 1031)
+    ]);
+    assert_eq!(
+        resource_directory
+            .get_manifest_resource(id(ISOLATIONAWARE_MANIFEST_RESOURCE_ID), 1031)
+            .unwrap()
+            .as_deref(),
+        Some(manifest.as_str())
+    );
+    assert_eq!(
+        resource_directory.get_manifest_resource(id(2), LANGUAGE_ID_EN_US).unwrap(),
+        None
+    );
+    assert_eq!(resource_directory.get_manifest().unwrap(), Some(original.clone()));
+
+    resource_directory.remove_manifest_resource(id(1), LANGUAGE_ID_EN_US).unwrap();
+    assert_eq!(resource_directory.manifests().unwrap(), vec![(id(2), 1031)]);
+    assert_eq!(resource_directory.get_manifest().unwrap(), Some(manifest));
+}
+
+#[test]
+fn manifest_encoding() {
+    init_logger();
+
+    let data_large = std::fs::read(BINARY_PATH_LARGE).unwrap();
+    let image_large = Image::parse(&data_large[..]).unwrap();
+    let mut resource_directory = image_large.resource_directory().cloned().unwrap();
+    let (original, encoding) = resource_directory.get_manifest_with_encoding().unwrap().unwrap();
+    assert_eq!(encoding, ManifestEncoding::Utf8);
+
+    let manifest = original.replace("UTF-8", "UTF-16");
+    for encoding in [
+        ManifestEncoding::Utf8Bom,
+        ManifestEncoding::Utf16LE,
+        ManifestEncoding::Utf16LEBom,
+        ManifestEncoding::Utf16BE,
+        ManifestEncoding::Utf16BEBom,
+    ] {
+        let data = encoding.encode(&manifest);
+        assert_eq!(ManifestEncoding::detect(&data).unwrap(), encoding);
+        assert_eq!(encoding.decode(&data).unwrap(), manifest);
+
+        resource_directory.set_manifest_with_encoding(&manifest, encoding).unwrap();
+        assert_eq!(
+            resource_directory.get_manifest_with_encoding().unwrap(),
+            Some((manifest.clone(), encoding)),
+            "{:?} manifest round-trips",
+            encoding
+        );
+        assert_eq!(resource_directory.get_manifest().unwrap().as_deref(), Some(manifest.as_str()));
+    }
+    assert_eq!(ManifestEncoding::Utf16LEBom.encode("<a/>"), b"\xFF\xFE<\0a\0/\0>\0");
+
+    // utf-16 is detected without a declaration and truncated data is not decoded
+    let manifest = r#"<assembly xmlns="urn:schemas-microsoft-com:asm.v1" manifestVersion="1.0"/>"#;
+    for encoding in [ManifestEncoding::Utf16LE, ManifestEncoding::Utf16BE] {
+        let mut data = encoding.encode(manifest);
+        assert_eq!(ManifestEncoding::detect(&data).unwrap(), encoding);
+        data.push(0);
+        assert!(encoding.decode(&data).is_err(), "odd byte is reported");
+    }
+    assert_eq!(
+        ManifestEncoding::detect(b"<?xml version='1.0' encoding='utf-8'?><assembly/>").unwrap(),
+        ManifestEncoding::Utf8
+    );
+    assert!(ManifestEncoding::Utf8.decode(b"<a>\xFF</a>").is_err());
+
+    // single byte encodings are decoded as declared
+    let data = b"<?xml version=\"1.0\" encoding=\"ISO-8859-1\"?>\n<assembly xmlns=\"urn:schemas-microsoft-com:asm.v1\" manifestVersion=\"1.0\"><description>Caf\xE9 \x80</description></assembly>";
+    let encoding = ManifestEncoding::detect(data).unwrap();
+    assert_eq!(encoding, ManifestEncoding::Latin1);
+    let manifest = encoding.decode(data).unwrap();
+    assert!(manifest.contains("Café \u{80}"));
+    assert_eq!(encoding.encode(&manifest), data);
+    resource_directory.set_manifest_with_encoding(&manifest, encoding).unwrap();
+    assert_eq!(
+        resource_directory.get_manifest_with_encoding().unwrap(),
+        Some((manifest.clone(), encoding))
+    );
+    assert_eq!(encoding.encode("€ 🦀"), b"&#x20AC; &#x1F980;");
+
+    let data = b"<?xml version='1.0' encoding='windows-1252'?><assembly>\x80\x81\xE9</assembly>";
+    let encoding = ManifestEncoding::detect(data).unwrap();
+    assert_eq!(encoding, ManifestEncoding::Windows1252);
+    let manifest = encoding.decode(data).unwrap();
+    assert!(manifest.contains(">€\u{81}é<"));
+    assert_eq!(encoding.encode(&manifest), data);
+
+    let data = b"<?xml version='1.0' encoding='Shift_JIS'?><assembly/>";
+    assert!(ManifestEncoding::detect(data).is_err(), "unsupported encoding is reported");
+    resource_directory
+        .set_manifest_with_encoding(core::str::from_utf8(data).unwrap(), ManifestEncoding::Utf8)
+        .unwrap();
+    assert!(resource_directory.get_manifest().is_err());
+}
+
+#[test]
+fn retag_and_remove_language() {
+    init_logger();
+
+    let mut resource_directory = ResourceDirectory::default();
+    let paths = [
+        ResourcePath::new(ResourceType::RCData, "A", LANG_NEUTRAL),
+        ResourcePath::new(ResourceType::RCData, "B", LANG_NEUTRAL),
+        ResourcePath::new(ResourceType::RCData, "B", LANGUAGE_ID_EN_US),
+        ResourcePath::new(ResourceType::HTML, 1, 0x0407),
+    ];
+    for (index, path) in paths.iter().enumerate() {
+        resource_directory.set_resource(path, vec![index as u8]).unwrap();
+    }
+    let data = |resource_directory: &ResourceDirectory, path: &ResourcePath| {
+        resource_directory.get_resource(path).unwrap().map(|data| data.data().to_vec())
+    };
+
+    let mut conflicting = resource_directory.clone();
+    assert!(matches!(
+        conflicting.retag_language(LANG_NEUTRAL, LANGUAGE_ID_EN_US, ConflictPolicy::Error),
+        Err(ResourceError::LanguageConflict(_))
+    ));
+    assert_eq!(conflicting, resource_directory, "nothing changed on conflict");
+
+    let mut skipped = resource_directory.clone();
+    assert_eq!(
+        skipped
+            .retag_language(LANG_NEUTRAL, LANGUAGE_ID_EN_US, ConflictPolicy::Skip)
+            .unwrap(),
+        1
+    );
+    assert_eq!(data(&skipped, &paths[1]), Some(vec![1]));
+    assert_eq!(data(&skipped, &paths[2]), Some(vec![2]));
+
+    assert_eq!(
+        resource_directory
+            .retag_language(LANG_NEUTRAL, LANGUAGE_ID_EN_US, ConflictPolicy::Replace)
+            .unwrap(),
+        2
+    );
+    assert_eq!(data(&resource_directory, &paths[0]), None);
+    assert_eq!(
+        data(&resource_directory, &ResourcePath::new(RT_RCDATA, "A", LANGUAGE_ID_EN_US)),
+        Some(vec![0])
+    );
+    assert_eq!(data(&resource_directory, &paths[1]), None);
+    assert_eq!(data(&resource_directory, &paths[2]), Some(vec![1]));
+
+    let resources = resource_directory.build(0x1000);
+    let rebuilt = ResourceDirectory::parse(&resources, 0, 0x1000).unwrap();
+    assert_eq!(rebuilt.resources().count(), 3);
+
+    assert_eq!(resource_directory.remove_language(LANGUAGE_ID_EN_US).unwrap(), 2);
+    assert_eq!(resource_directory.remove_language(LANGUAGE_ID_EN_US).unwrap(), 0);
+    assert!(resource_directory.root().get(ResourceType::RCData).is_none());
+    assert_eq!(resource_directory.resources().map(|(path, _)| path).collect::<Vec<_>>(), vec![
+        paths[3].clone()
+    ]);
+}